@@ -0,0 +1,92 @@
+//! Compares dispatching window events through the event loop's routing table
+//! with looking each window up in the shared `Windows` view, while other
+//! threads query the open windows.
+//!
+//! - `routes`: `WindowRoutes::send`, which the event loop uses to dispatch
+//!   every window event without taking any shared lock.
+//! - `shared`: takes the read lock guarding the `Windows` view for every
+//!   event and sends through the window found there.
+//!
+//! Run with `cargo bench --bench dispatch --features bench`.
+
+use std::hint::black_box;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use appit::bench::Dispatcher;
+use appit::winit::window::WindowId;
+
+const EVENTS: usize = 1_000_000;
+const READERS: usize = 4;
+const DRAINS: usize = 2;
+
+fn main() {
+    for windows in [1, 16, 128, 1024] {
+        let (routes, routes_dropped) = bench(windows, Dispatcher::dispatch);
+        let (shared, shared_dropped) = bench(windows, Dispatcher::dispatch_shared);
+        println!(
+            "{windows:>5} windows: routes {:>7.1}ns/event, shared {:>7.1}ns/event ({:.2}x)",
+            per_event(routes),
+            per_event(shared),
+            shared.as_secs_f64() / routes.as_secs_f64()
+        );
+        if routes_dropped + shared_dropped > 0 {
+            println!(
+                "{:>14} dropped {routes_dropped} routed and {shared_dropped} shared events",
+                ""
+            );
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn per_event(elapsed: Duration) -> f64 {
+    elapsed.as_nanos() as f64 / EVENTS as f64
+}
+
+/// Dispatches [`EVENTS`] events round-robin across `count` windows using
+/// `dispatch`, returning the time taken and the number of events that could
+/// not be enqueued.
+fn bench(count: usize, dispatch: fn(&Dispatcher, WindowId) -> bool) -> (Duration, usize) {
+    let dispatcher = Dispatcher::open(count, DRAINS);
+    let stop = Arc::new(AtomicBool::new(false));
+    let readers = spawn_readers(&dispatcher, &stop);
+    let ids = dispatcher.ids();
+
+    let mut dropped = 0;
+    let start = Instant::now();
+    for event in 0..EVENTS {
+        if !dispatch(&dispatcher, ids[event % ids.len()]) {
+            dropped += 1;
+        }
+    }
+    let elapsed = start.elapsed();
+
+    stop.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().expect("reader panicked");
+    }
+    (elapsed, dropped)
+}
+
+/// Spawns threads that repeatedly look up the open windows until `stop` is
+/// set.
+fn spawn_readers(dispatcher: &Dispatcher, stop: &Arc<AtomicBool>) -> Vec<JoinHandle<()>> {
+    (0..READERS)
+        .map(|_| {
+            let windows = dispatcher.windows();
+            let ids = dispatcher.ids().to_vec();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                for id in ids.iter().cycle() {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    black_box(windows.handle(*id));
+                }
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,155 @@
+//! Hooks for benchmarking event dispatch without a display.
+//!
+//! This module is only compiled with the `bench` feature and is not part of
+//! the public API.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use winit::dpi::PhysicalPosition;
+use winit::event::DeviceId;
+use winit::window::WindowId;
+
+use crate::private::{OpenedWindow, WindowEvent, WindowMessage, WindowReceiver, WindowSender};
+use crate::{OpenWindow, WindowRoute, WindowRoutes, Windows};
+
+/// A set of open windows that events can be dispatched to.
+///
+/// Each window is registered in both the event loop's routing table and the
+/// [`Windows`] view shared with other threads, exactly as opening a real
+/// window does. Background threads drain the windows' queues so that
+/// dispatching never finds a full queue.
+pub struct Dispatcher {
+    routes: WindowRoutes<(), ()>,
+    windows: Windows<()>,
+    ids: Vec<WindowId>,
+    running: Arc<AtomicBool>,
+    drains: Vec<JoinHandle<()>>,
+}
+
+impl Dispatcher {
+    /// Opens `count` windows whose queues are drained by `drains` threads.
+    #[must_use]
+    pub fn open(count: usize, drains: usize) -> Self {
+        let mut routes = WindowRoutes::default();
+        let windows = Windows::default();
+        let mut ids = Vec::with_capacity(count);
+        let mut receivers = (0..drains.max(1)).map(|_| Vec::new()).collect::<Vec<_>>();
+        {
+            let mut data = windows.data.write().unwrap_or_else(PoisonError::into_inner);
+            for index in 0..count {
+                let id = WindowId::from(index as u64);
+                let (sender, receiver) = WindowSender::queue();
+                let sender = Arc::new(sender);
+                routes.insert(
+                    id,
+                    WindowRoute {
+                        sender: sender.clone(),
+                        tag: None,
+                        sync: (),
+                        local: false,
+                    },
+                );
+                let opened_order = data.next_sequence();
+                data.open.insert(
+                    id,
+                    OpenWindow {
+                        winit: OpenedWindow::pending(),
+                        sender,
+                        opened_order,
+                        focused: false,
+                        last_focused: 0,
+                        tag: None,
+                    },
+                );
+                ids.push(id);
+                let drain = index % receivers.len();
+                receivers[drain].push(receiver);
+            }
+        }
+
+        let running = Arc::new(AtomicBool::new(true));
+        let drains = receivers
+            .into_iter()
+            .map(|receivers| {
+                let running = running.clone();
+                thread::spawn(move || drain(&receivers, &running))
+            })
+            .collect();
+        Self {
+            routes,
+            windows,
+            ids,
+            running,
+            drains,
+        }
+    }
+
+    /// Returns the ids of the open windows.
+    #[must_use]
+    pub fn ids(&self) -> &[WindowId] {
+        &self.ids
+    }
+
+    /// Returns the view of the open windows shared with other threads.
+    #[must_use]
+    pub fn windows(&self) -> Windows<()> {
+        self.windows.clone()
+    }
+
+    /// Dispatches a cursor movement to `window` through the event loop's
+    /// routing table, returning true if it was enqueued.
+    #[must_use]
+    pub fn dispatch(&self, window: WindowId) -> bool {
+        self.routes.send(window, cursor_moved())
+    }
+
+    /// Dispatches a cursor movement to `window` by looking it up in the
+    /// shared [`Windows`] view, returning true if it was enqueued.
+    #[must_use]
+    pub fn dispatch_shared(&self, window: WindowId) -> bool {
+        let data = self
+            .windows
+            .data
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        data.open
+            .get(&window)
+            .is_some_and(|open| open.sender.try_send(cursor_moved()).is_ok())
+    }
+}
+
+impl Drop for Dispatcher {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        for drain in self.drains.drain(..) {
+            let _result = drain.join();
+        }
+    }
+}
+
+fn cursor_moved() -> WindowMessage<()> {
+    WindowMessage::Event(
+        WindowEvent::CursorMoved {
+            device_id: DeviceId::dummy(),
+            position: PhysicalPosition::new(0., 0.),
+        },
+        Instant::now(),
+    )
+}
+
+fn drain(receivers: &[WindowReceiver<()>], running: &AtomicBool) {
+    while running.load(Ordering::Relaxed) {
+        let mut received = false;
+        for receiver in receivers {
+            while receiver.try_recv().is_some() {
+                received = true;
+            }
+        }
+        if !received {
+            thread::yield_now();
+        }
+    }
+}
@@ -4,22 +4,36 @@
 #![allow(clippy::module_name_repetitions)]
 #![allow(clippy::missing_panics_doc)] // https://github.com/rust-lang/rust-clippy/issues/11436
 
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench;
+mod pool;
 mod private;
+mod scroll;
 mod window;
 
 #[cfg(all(target_os = "linux", feature = "xdg"))]
 mod xdg;
 
-use std::collections::HashMap;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::ops::Deref;
 use std::process::exit;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{mpsc, Arc, Mutex, PoisonError};
-use std::time::Duration;
-
-use private::{OpenedWindow, WindowSpawner};
-pub use window::{Run, RunningWindow, Window, WindowAttributes, WindowBehavior, WindowBuilder};
+use std::sync::{mpsc, Arc, PoisonError, RwLock};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use pool::WorkerPool;
+use private::{DriveWindow, LocalSpawner, OpenedWindow, Pump, WindowSender, WindowSpawner};
+pub use scroll::{ScrollPhase, ScrollSettings};
+pub use window::{
+    EventCategories, ExecutionMode, OpenError, RedrawSync, Run, RunningWindow, RuntimeErrorAction,
+    ThreadAttributes, TrySendError, Window, WindowAttributes, WindowBehavior, WindowBuilder,
+    WindowStatistics, WindowTag,
+};
 pub use winit;
 use winit::application::ApplicationHandler;
 use winit::error::{EventLoopError, OsError};
@@ -41,16 +55,19 @@ impl<'a, AppMessage> ExecutingApp<'a, AppMessage>
 where
     AppMessage: Message,
 {
-    fn new(
-        windows: &'a Windows<<AppMessage as Message>::Window>,
-        winit: impl Into<WinitHandle<'a, AppMessage>>,
-    ) -> Self {
+    fn new(app: &'a App<AppMessage>, winit: impl Into<WinitHandle<'a, AppMessage>>) -> Self {
         Self(ExecutingAppHandle {
-            windows,
+            app,
             winit: winit.into(),
         })
     }
 
+    /// Returns a handle to the application.
+    #[must_use]
+    pub fn app(&self) -> App<AppMessage> {
+        self.0.app.clone()
+    }
+
     /// Returns the list of available monitors.
     ///
     /// This function will return an empty `Vec` if invoked before the
@@ -95,7 +112,7 @@ where
     type Target = Windows<AppMessage::Window>;
 
     fn deref(&self) -> &Self::Target {
-        self.0.windows
+        &self.0.app.windows
     }
 }
 
@@ -103,7 +120,7 @@ struct ExecutingAppHandle<'a, AppMessage>
 where
     AppMessage: Message,
 {
-    windows: &'a Windows<<AppMessage as Message>::Window>,
+    app: &'a App<AppMessage>,
     winit: WinitHandle<'a, AppMessage>,
 }
 
@@ -144,7 +161,18 @@ where
     running: App<AppMessage>,
     on_startup: Vec<Box<StartupClosure<AppMessage>>>,
     pending_windows: Vec<PendingWindow<AppMessage>>,
-    on_error: Option<Box<dyn FnMut(AppMessage::Error)>>,
+    on_error: Option<BoxedErrorCallback<AppMessage>>,
+    on_window_opened: Option<BoxedWindowCallback<WindowOpened, AppMessage>>,
+    on_window_open_failed: Option<BoxedWindowCallback<WindowOpenFailed, AppMessage>>,
+    on_window_closed: Option<BoxedWindowCallback<WindowClosed, AppMessage>>,
+    quit_policy: QuitPolicy<AppMessage>,
+    quit_timeout: Duration,
+    quit_timeout_action: QuitTimeoutAction,
+    panic_policy: PanicPolicy,
+    on_window_panic: Option<BoxedWindowCallback<WindowPanic, AppMessage>>,
+    unresponsive_threshold: Duration,
+    on_window_unresponsive: Option<BoxedUnresponsiveCallback<AppMessage>>,
+    on_window_responsive: Option<BoxedWindowCallback<WindowResponsive, AppMessage>>,
 }
 
 struct PendingWindow<AppMessage>
@@ -152,13 +180,40 @@ where
     AppMessage: Message,
 {
     window: WindowAttributes,
-    sender: Arc<mpsc::SyncSender<WindowMessage<AppMessage::Window>>>,
-    spawner: WindowSpawner,
+    sender: Arc<WindowSender<AppMessage::Window>>,
+    spawner: LocalSpawner<AppMessage>,
+    /// The handle that is filled in once the window is opened.
+    opened: OpenedWindow,
+}
+
+thread_local! {
+    /// Windows opened with [`WindowBuilder::open_pinned`] through an [`App`].
+    /// Each entry is a `PendingWindow<AppMessage>`, opened the next time the
+    /// event loop is about to wait.
+    static PINNED_WINDOWS: RefCell<Vec<Box<dyn Any>>> = const { RefCell::new(Vec::new()) };
 }
 
 type BoxedEventCallback<AppMessage> =
     Box<dyn FnMut(AppMessage, ExecutingApp<'_, AppMessage>) -> <AppMessage as Message>::Response>;
 
+type BoxedErrorCallback<AppMessage> = Box<dyn FnMut(<AppMessage as Message>::Error, ErrorContext)>;
+
+type BoxedWindowCallback<Event, AppMessage> = Box<dyn FnMut(Event, ExecutingApp<'_, AppMessage>)>;
+type BoxedUnresponsiveCallback<AppMessage> =
+    Box<dyn FnMut(WindowUnresponsive, ExecutingApp<'_, AppMessage>) -> UnresponsiveAction>;
+
+/// The default duration a window can leave messages unprocessed before it is
+/// considered unresponsive.
+const DEFAULT_UNRESPONSIVE_THRESHOLD: Duration = Duration::from_secs(5);
+
+/// The default duration the app waits for windows to respond to a quit
+/// request.
+const DEFAULT_QUIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The longest the event loop waits for a window using
+/// [`RedrawSync::Synchronous`] to redraw when no resize timeout is set.
+const SYNCHRONOUS_REDRAW_TIMEOUT: Duration = Duration::from_secs(1);
+
 impl Default for PendingApp<()> {
     fn default() -> Self {
         Self::new()
@@ -192,26 +247,152 @@ where
         Self {
             event_loop,
             running: App {
+                workers: Arc::new(WorkerPool::new(proxy.clone())),
                 proxy,
                 windows: Windows::default(),
                 started: Arc::new(AtomicBool::new(false)),
+                event_loop_thread: thread::current().id(),
             },
             message_callback: Box::new(event_callback),
             on_startup: Vec::new(),
             pending_windows: Vec::new(),
             on_error: None,
+            on_window_opened: None,
+            on_window_open_failed: None,
+            on_window_closed: None,
+            quit_policy: QuitPolicy::default(),
+            quit_timeout: DEFAULT_QUIT_TIMEOUT,
+            quit_timeout_action: QuitTimeoutAction::default(),
+            panic_policy: PanicPolicy::default(),
+            on_window_panic: None,
+            unresponsive_threshold: DEFAULT_UNRESPONSIVE_THRESHOLD,
+            on_window_unresponsive: None,
+            on_window_responsive: None,
         }
     }
 
     /// Sets a handler that is invoked when an app receives an
-    /// [`AppTypes::Error`].
+    /// [`Message::Error`].
+    ///
+    /// The handler receives an [`ErrorContext`] describing where the error
+    /// originated.
     pub fn on_error<F>(&mut self, on_error: F)
     where
-        F: FnMut(AppMessage::Error) + 'static,
+        F: FnMut(AppMessage::Error, ErrorContext) + 'static,
     {
         self.on_error = Some(Box::new(on_error));
     }
 
+    /// Sets a handler that is invoked each time a window is opened.
+    pub fn on_window_opened<F>(&mut self, on_opened: F)
+    where
+        F: FnMut(WindowOpened, ExecutingApp<'_, AppMessage>) + 'static,
+    {
+        self.on_window_opened = Some(Box::new(on_opened));
+    }
+
+    /// Sets a handler that is invoked when a window opened with
+    /// [`WindowBuilder::open_pinned`] fails to open.
+    ///
+    /// Pinned windows are opened after [`WindowBuilder::open_pinned`] has
+    /// returned, so errors can't be returned to the caller. If no handler is
+    /// installed, these errors are ignored.
+    pub fn on_window_open_failed<F>(&mut self, on_failed: F)
+    where
+        F: FnMut(WindowOpenFailed, ExecutingApp<'_, AppMessage>) + 'static,
+    {
+        self.on_window_open_failed = Some(Box::new(on_failed));
+    }
+
+    /// Sets a handler that is invoked each time a window is closed.
+    ///
+    /// The handler is invoked after the window has been removed from the
+    /// list of open windows.
+    pub fn on_window_closed<F>(&mut self, on_closed: F)
+    where
+        F: FnMut(WindowClosed, ExecutingApp<'_, AppMessage>) + 'static,
+    {
+        self.on_window_closed = Some(Box::new(on_closed));
+    }
+
+    /// Sets the policy that determines when this app automatically quits.
+    ///
+    /// By default, [`QuitPolicy::LastWindowClosed`] is used. Regardless of the
+    /// policy, the app will not quit while a [`ShutdownGuard`] exists.
+    pub fn set_quit_policy(&mut self, policy: QuitPolicy<AppMessage>) {
+        self.quit_policy = policy;
+    }
+
+    /// Sets how long [`App::quit`] waits for every window to respond before
+    /// taking `action`. Defaults to 5 seconds and
+    /// [`QuitTimeoutAction::Cancel`].
+    pub fn set_quit_timeout(&mut self, timeout: Duration, action: QuitTimeoutAction) {
+        self.quit_timeout = timeout;
+        self.quit_timeout_action = action;
+    }
+
+    /// Sets the policy that determines how the app responds to a window's
+    /// behavior panicking.
+    ///
+    /// By default, [`PanicPolicy::CloseWindow`] is used.
+    pub fn set_panic_policy(&mut self, policy: PanicPolicy) {
+        self.panic_policy = policy;
+    }
+
+    /// Sets a handler that is invoked each time a window's behavior panics.
+    ///
+    /// The handler is invoked before the app's [`PanicPolicy`] is applied.
+    pub fn on_window_panic<F>(&mut self, on_panic: F)
+    where
+        F: FnMut(WindowPanic, ExecutingApp<'_, AppMessage>) + 'static,
+    {
+        self.on_window_panic = Some(Box::new(on_panic));
+    }
+
+    /// Sets how long a window can leave messages unprocessed before it is
+    /// considered unresponsive. Defaults to 5 seconds.
+    ///
+    /// Windows are checked as the event loop processes events. Windows whose
+    /// behavior runs on the event loop thread are not checked, as the event
+    /// loop is blocked while they are. Windows waiting to be restarted after
+    /// a panic are not checked until their behavior has been restarted.
+    pub fn set_unresponsive_threshold(&mut self, threshold: Duration) {
+        self.unresponsive_threshold = threshold;
+    }
+
+    /// Sets a handler that is invoked when a window stops responding.
+    ///
+    /// The handler is invoked once each time a window exceeds the
+    /// [unresponsive threshold](Self::set_unresponsive_threshold), and returns
+    /// the [`UnresponsiveAction`] to take. Without a handler, the app waits
+    /// for the window to recover. [`Window::is_responding`] reports whether a
+    /// window is currently responding.
+    pub fn on_window_unresponsive<F>(&mut self, on_unresponsive: F)
+    where
+        F: FnMut(WindowUnresponsive, ExecutingApp<'_, AppMessage>) -> UnresponsiveAction + 'static,
+    {
+        self.on_window_unresponsive = Some(Box::new(on_unresponsive));
+    }
+
+    /// Sets a handler that is invoked when a window that was detected as not
+    /// responding processes a message again.
+    pub fn on_window_responsive<F>(&mut self, on_responsive: F)
+    where
+        F: FnMut(WindowResponsive, ExecutingApp<'_, AppMessage>) + 'static,
+    {
+        self.on_window_responsive = Some(Box::new(on_responsive));
+    }
+
+    /// Sets the maximum number of worker threads used to run windows opened
+    /// with [`ExecutionMode::Shared`].
+    ///
+    /// By default, the number of threads is the available parallelism
+    /// reported by the standard library. Worker threads are only spawned as
+    /// windows are opened.
+    pub fn set_worker_threads(&mut self, threads: usize) {
+        self.running.workers.set_max_workers(threads);
+    }
+
     /// Executes `on_startup` once the app event loop has started.
     ///
     /// This is useful because some information provided by winit is only
@@ -241,6 +422,17 @@ where
             on_startup,
             pending_windows,
             on_error,
+            on_window_opened,
+            on_window_open_failed,
+            on_window_closed,
+            quit_policy,
+            quit_timeout,
+            quit_timeout_action,
+            panic_policy,
+            on_window_panic,
+            unresponsive_threshold,
+            on_window_unresponsive,
+            on_window_responsive,
         } = self;
 
         #[cfg(all(target_os = "linux", feature = "xdg"))]
@@ -252,6 +444,24 @@ where
             on_startup,
             pending_windows,
             on_error,
+            on_window_opened,
+            on_window_open_failed,
+            on_window_closed,
+            quit_policy,
+            quit_timeout,
+            quit_timeout_action,
+            panic_policy,
+            on_window_panic,
+            unresponsive_threshold,
+            on_window_unresponsive,
+            on_window_responsive,
+            local_windows: HashMap::new(),
+            routes: WindowRoutes::default(),
+            shutdown_guards: 0,
+            main_window_closed: false,
+            pending_quit: None,
+            quit_requests: 0,
+            quitting: false,
         })
     }
 }
@@ -264,7 +474,440 @@ where
     running: App<AppMessage>,
     on_startup: Vec<Box<StartupClosure<AppMessage>>>,
     pending_windows: Vec<PendingWindow<AppMessage>>,
-    on_error: Option<Box<dyn FnMut(AppMessage::Error)>>,
+    on_error: Option<BoxedErrorCallback<AppMessage>>,
+    on_window_opened: Option<BoxedWindowCallback<WindowOpened, AppMessage>>,
+    on_window_open_failed: Option<BoxedWindowCallback<WindowOpenFailed, AppMessage>>,
+    on_window_closed: Option<BoxedWindowCallback<WindowClosed, AppMessage>>,
+    quit_policy: QuitPolicy<AppMessage>,
+    quit_timeout: Duration,
+    quit_timeout_action: QuitTimeoutAction,
+    panic_policy: PanicPolicy,
+    on_window_panic: Option<BoxedWindowCallback<WindowPanic, AppMessage>>,
+    unresponsive_threshold: Duration,
+    on_window_unresponsive: Option<BoxedUnresponsiveCallback<AppMessage>>,
+    on_window_responsive: Option<BoxedWindowCallback<WindowResponsive, AppMessage>>,
+    local_windows: HashMap<WindowId, LocalWindow<AppMessage>>,
+    routes: WindowRoutes<AppMessage::Window>,
+    shutdown_guards: usize,
+    main_window_closed: bool,
+    pending_quit: Option<PendingQuit>,
+    quit_requests: u64,
+    quitting: bool,
+}
+
+/// A window whose behavior is driven by the event loop.
+struct LocalWindow<AppMessage>
+where
+    AppMessage: Message,
+{
+    driver: Box<dyn DriveWindow<AppMessage>>,
+    wake_at: Option<Instant>,
+}
+
+struct PendingQuit {
+    request: u64,
+    awaiting: HashSet<WindowId>,
+    deadline: Instant,
+}
+
+impl<AppMessage> RunningApp<AppMessage>
+where
+    AppMessage: Message,
+{
+    fn open_window(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        attrs: WindowAttributes,
+        sender: Arc<WindowSender<AppMessage::Window>>,
+        spawner: LocalSpawner<AppMessage>,
+        opened: OpenedWindow,
+    ) -> Result<OpenedWindow, OsError> {
+        let tag = attrs.tag.clone();
+        let mut route = self
+            .running
+            .windows
+            .open(event_loop, attrs, sender, &opened)?;
+        let id = route.sync.winit.id();
+        let driver = spawner(opened.clone());
+        route.local = driver.is_some();
+        self.routes.insert(id, route);
+        if let Some(driver) = driver {
+            // The behavior is initialized the next time the event loop is
+            // about to wait.
+            self.local_windows.insert(
+                id,
+                LocalWindow {
+                    driver,
+                    wake_at: Some(Instant::now()),
+                },
+            );
+        }
+        if let Some(pending) = &mut self.pending_quit {
+            // Windows opened while a quit is pending must also agree to quit.
+            if self
+                .routes
+                .send(id, WindowMessage::QuitRequested(pending.request))
+            {
+                pending.awaiting.insert(id);
+            }
+        } else if self.quitting {
+            // The app is closing every window, including ones opened after
+            // the quit was agreed to.
+            self.routes.send(id, WindowMessage::Quit);
+        }
+        if let Some(on_opened) = &mut self.on_window_opened {
+            on_opened(
+                WindowOpened { id, tag },
+                ExecutingApp::new(&self.running, event_loop),
+            );
+        }
+        Ok(opened)
+    }
+
+    /// Closes the window, returning true if the app should shut down as a
+    /// result.
+    fn close_window(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        id: WindowId,
+        reason: CloseReason,
+    ) -> bool {
+        self.routes.remove(id);
+        let Some(closed) = self.running.windows.close(id) else {
+            // The window was already closed, such as when a window that was
+            // forcibly closed finally exits. Its close has already been
+            // accounted for.
+            return false;
+        };
+        if let (QuitPolicy::MainWindowClosed(main), Some(tag)) = (&self.quit_policy, &closed.tag) {
+            self.main_window_closed |= main == tag;
+        }
+        if let Some(on_closed) = &mut self.on_window_closed {
+            on_closed(
+                WindowClosed {
+                    id,
+                    tag: closed.tag,
+                    reason,
+                },
+                ExecutingApp::new(&self.running, event_loop),
+            );
+        }
+        if let Some(pending) = &mut self.pending_quit {
+            pending.awaiting.remove(&id);
+            self.commit_quit_if_ready();
+        }
+        self.should_quit(event_loop)
+    }
+
+    /// Flags windows that have left messages unprocessed for longer than the
+    /// unresponsive threshold, returning when windows should next be checked.
+    fn check_responsiveness(&mut self, event_loop: &ActiveEventLoop) -> Option<Instant> {
+        let (unresponsive, next_check) = self
+            .routes
+            .unresponsive(Instant::now(), self.unresponsive_threshold);
+        for (id, unresponsive_for) in unresponsive {
+            let action = match &mut self.on_window_unresponsive {
+                Some(on_unresponsive) => on_unresponsive(
+                    WindowUnresponsive {
+                        id,
+                        tag: self.routes.tag(id),
+                        unresponsive_for,
+                    },
+                    ExecutingApp::new(&self.running, event_loop),
+                ),
+                None => UnresponsiveAction::Wait,
+            };
+            if action == UnresponsiveAction::ForceClose {
+                self.routes.force_close(id);
+                if self.close_window(event_loop, id, CloseReason::Unresponsive) {
+                    exit(0);
+                }
+            }
+        }
+        next_check
+    }
+
+    fn window_responding(&mut self, event_loop: &ActiveEventLoop, id: WindowId) {
+        // The window may have been force closed before it recovered.
+        if !self.routes.contains(id) {
+            return;
+        }
+        if let Some(on_responsive) = &mut self.on_window_responsive {
+            on_responsive(
+                WindowResponsive {
+                    id,
+                    tag: self.routes.tag(id),
+                },
+                ExecutingApp::new(&self.running, event_loop),
+            );
+        }
+    }
+
+    fn should_quit(&mut self, event_loop: &ActiveEventLoop) -> bool {
+        if self.quitting {
+            return self.routes.is_empty();
+        } else if self.shutdown_guards > 0 {
+            return false;
+        }
+
+        match &mut self.quit_policy {
+            QuitPolicy::LastWindowClosed => self.routes.is_empty(),
+            QuitPolicy::MainWindowClosed(_) => self.main_window_closed,
+            QuitPolicy::Never => false,
+            QuitPolicy::Custom(should_quit) => {
+                should_quit(ExecutingApp::new(&self.running, event_loop))
+            }
+        }
+    }
+}
+
+impl<AppMessage> RunningApp<AppMessage>
+where
+    AppMessage: Message,
+{
+    fn request_quit(&mut self) {
+        if self.quitting || self.pending_quit.is_some() {
+            return;
+        }
+
+        self.quit_requests += 1;
+        let request = self.quit_requests;
+        let mut pending = PendingQuit {
+            request,
+            awaiting: HashSet::new(),
+            deadline: Instant::now() + self.quit_timeout,
+        };
+        let mut unreachable = false;
+        for id in self.routes.ids() {
+            if self.routes.send(id, WindowMessage::QuitRequested(request)) {
+                pending.awaiting.insert(id);
+            } else {
+                unreachable = true;
+            }
+        }
+        self.pending_quit = Some(pending);
+
+        if unreachable {
+            // A window that can't be asked can't agree to quit.
+            self.cancel_quit(None);
+        } else {
+            self.commit_quit_if_ready();
+        }
+    }
+
+    fn quit_response(&mut self, window: WindowId, request: u64, allowed: bool) {
+        let Some(pending) = &mut self.pending_quit else {
+            return;
+        };
+        if pending.request != request {
+            return;
+        }
+
+        if allowed {
+            pending.awaiting.remove(&window);
+            self.commit_quit_if_ready();
+        } else {
+            self.cancel_quit(Some(window));
+        }
+    }
+
+    fn commit_quit_if_ready(&mut self) {
+        if !self
+            .pending_quit
+            .as_ref()
+            .is_some_and(|pending| pending.awaiting.is_empty())
+        {
+            return;
+        }
+
+        self.pending_quit = None;
+        self.quitting = true;
+        let windows = self.routes.ids();
+        if windows.is_empty() {
+            exit(0);
+        }
+        for id in windows {
+            self.routes.send(id, WindowMessage::Quit);
+        }
+    }
+
+    /// Applies the quit timeout action if windows have not responded to the
+    /// pending quit in time, returning when to check again.
+    fn check_quit_timeout(&mut self, event_loop: &ActiveEventLoop) -> Option<Instant> {
+        let pending = self.pending_quit.as_mut()?;
+        if pending.deadline > Instant::now() {
+            return Some(pending.deadline);
+        }
+        match self.quit_timeout_action {
+            QuitTimeoutAction::Cancel => self.cancel_quit(None),
+            QuitTimeoutAction::Quit => {
+                let unanswered = std::mem::take(&mut pending.awaiting);
+                self.commit_quit_if_ready();
+                // Windows that never answered may be deadlocked, and would
+                // keep the app running forever.
+                for id in unanswered {
+                    // Behaviors driven by the event loop can be dropped right
+                    // away, before the window is closed.
+                    drop(self.local_windows.remove(&id));
+                    self.routes.force_close(id);
+                    if self.close_window(event_loop, id, CloseReason::Unresponsive) {
+                        exit(0);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn cancel_quit(&mut self, vetoed_by: Option<WindowId>) {
+        self.pending_quit = None;
+        for id in self.routes.ids() {
+            if Some(id) != vetoed_by {
+                self.routes.send(id, WindowMessage::QuitCancelled);
+            }
+        }
+    }
+}
+
+impl<AppMessage> RunningApp<AppMessage>
+where
+    AppMessage: Message,
+{
+    fn report_error(
+        &mut self,
+        error: AppMessage::Error,
+        window: Option<WindowId>,
+        phase: ErrorPhase,
+    ) {
+        if let Some(handler) = &mut self.on_error {
+            handler(
+                error,
+                ErrorContext {
+                    tag: window.and_then(|window| self.routes.tag(window)),
+                    window,
+                    phase,
+                },
+            );
+        }
+    }
+
+    fn window_panicked(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        panic: WindowPanic,
+        restartable: bool,
+        restart_sender: &mpsc::SyncSender<Duration>,
+    ) {
+        let window_id = panic.id;
+        let restarts = panic.restarts;
+        if let Some(on_panic) = &mut self.on_window_panic {
+            on_panic(panic, ExecutingApp::new(&self.running, event_loop));
+        }
+        match self.panic_policy {
+            PanicPolicy::Exit => exit(1),
+            PanicPolicy::Restart {
+                max_restarts,
+                backoff,
+            } if restartable && restarts < max_restarts => {
+                let delay = backoff.saturating_mul(2_u32.saturating_pow(restarts.min(31)));
+                let _result = restart_sender.send(delay);
+            }
+            PanicPolicy::CloseWindow | PanicPolicy::Restart { .. } => {
+                if self.close_window(event_loop, window_id, CloseReason::Panic) {
+                    exit(1)
+                }
+            }
+        }
+    }
+}
+
+impl<AppMessage> RunningApp<AppMessage>
+where
+    AppMessage: Message,
+{
+    fn pump_local_window(&mut self, event_loop: &ActiveEventLoop, id: WindowId) {
+        // The window is removed while it is being driven, as its behavior may
+        // cause the event loop to be re-entered.
+        let Some(mut local) = self.local_windows.remove(&id) else {
+            return;
+        };
+        match local.driver.pump() {
+            Pump::Wait(wake_at) => {
+                local.wake_at = wake_at;
+                self.local_windows.insert(id, local);
+            }
+            Pump::Exited(message) => {
+                drop(local);
+                if let Some(message) = message {
+                    self.user_event(event_loop, message);
+                }
+            }
+            Pump::Panicked(payload) => {
+                let (restart_sender, restart_receiver) = mpsc::sync_channel(1);
+                self.window_panicked(
+                    event_loop,
+                    WindowPanic {
+                        id,
+                        tag: self.routes.tag(id),
+                        payload,
+                        restarts: local.driver.restarts(),
+                    },
+                    local.driver.restartable(),
+                    &restart_sender,
+                );
+                if let Ok(delay) = restart_receiver.try_recv() {
+                    local.driver.restart_after(delay);
+                    local.wake_at = Some(Instant::now() + delay);
+                    self.local_windows.insert(id, local);
+                }
+            }
+        }
+    }
+
+    fn open_pinned_windows(&mut self, event_loop: &ActiveEventLoop) {
+        loop {
+            let pinned = PINNED_WINDOWS.with(|pinned| std::mem::take(&mut *pinned.borrow_mut()));
+            if pinned.is_empty() {
+                break;
+            }
+            for pending in pinned {
+                let Ok(pending) = pending.downcast::<PendingWindow<AppMessage>>() else {
+                    continue;
+                };
+                let PendingWindow {
+                    window,
+                    sender,
+                    spawner,
+                    opened,
+                } = *pending;
+                let tag = window.tag.clone();
+                if let Err(error) = self.open_window(event_loop, window, sender, spawner, opened) {
+                    if let Some(on_failed) = &mut self.on_window_open_failed {
+                        on_failed(
+                            WindowOpenFailed {
+                                tag,
+                                error: error.into(),
+                            },
+                            ExecutingApp::new(&self.running, event_loop),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn pump_due_local_windows(&mut self, event_loop: &ActiveEventLoop) {
+        let now = Instant::now();
+        let due = self
+            .local_windows
+            .iter()
+            .filter(|(_, local)| private::pump_due(&*local.driver, local.wake_at, now))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in due {
+            self.pump_local_window(event_loop, id);
+        }
+    }
 }
 
 impl<AppMessage> ApplicationHandler<EventLoopMessage<AppMessage>> for RunningApp<AppMessage>
@@ -280,18 +923,15 @@ where
             window,
             sender,
             spawner,
-        } in self.pending_windows.drain(..)
+            opened,
+        } in std::mem::take(&mut self.pending_windows)
         {
             // TODO how to handle open failure errors for pending windows?
-            let window = self
-                .running
-                .windows
-                .open(event_loop, window, sender)
+            self.open_window(event_loop, window, sender, spawner, opened)
                 .expect("error spawning initial window");
-            spawner(window);
         }
         for on_startup in self.on_startup.drain(..) {
-            on_startup(ExecutingApp::new(&self.running.windows, event_loop));
+            on_startup(ExecutingApp::new(&self.running, event_loop));
         }
     }
 
@@ -301,29 +941,124 @@ where
 
     fn window_event(
         &mut self,
-        _event_loop: &ActiveEventLoop,
+        event_loop: &ActiveEventLoop,
         window_id: WindowId,
         event: winit::event::WindowEvent,
     ) {
-        let (event, waiter) = WindowEvent::from_winit(event);
-        self.running
-            .windows
-            .send(window_id, WindowMessage::Event(event));
-        if let Some(waiter) = waiter {
-            waiter.wait(Duration::from_millis(16));
+        if let winit::event::WindowEvent::Focused(focused) = &event {
+            self.running.windows.focus_changed(window_id, *focused);
+        }
+        let sync = self.routes.sync(window_id);
+        if sync
+            .as_ref()
+            .is_some_and(|sync| !sync.events.includes(&event))
+        {
+            return;
         }
+        let is_redraw = matches!(event, winit::event::WindowEvent::RedrawRequested);
+        let is_rescale = matches!(event, winit::event::WindowEvent::ScaleFactorChanged { .. });
+        let is_resize = matches!(event, winit::event::WindowEvent::Resized(_));
+        let received = Instant::now();
+        let (event, waiter) = WindowEvent::from_winit(
+            event,
+            sync.as_ref()
+                .is_some_and(|sync| sync.resize_timeout.is_some()),
+        );
+        if let Some(local) = self.local_windows.get_mut(&window_id) {
+            local.driver.deliver(WindowMessage::Event(event, received));
+            if is_redraw || is_resize || is_rescale {
+                // The operating system expects these to be handled before
+                // this event returns.
+                self.pump_local_window(event_loop, window_id);
+            } else {
+                // Other events are batched and handled together once the
+                // event loop is about to wait, allowing the window to draw
+                // once for many input events.
+                local.wake_at = Some(received);
+            }
+            // If the window did not handle the event, such as while it is
+            // restarting, only this thread can pump it. Waiting would never
+            // succeed.
+            return;
+        }
+        let _sent = self
+            .routes
+            .send(window_id, WindowMessage::Event(event, received));
+        let (Some(waiter), Some(sync)) = (waiter, sync) else {
+            return;
+        };
+        if !self.routes.responding(window_id) {
+            // Waiting on a window that isn't responding would stall the event
+            // loop for the full timeout on every event.
+            return;
+        }
+        let timeout = if is_redraw {
+            match sync.redraw {
+                RedrawSync::Synchronous => {
+                    Some(sync.resize_timeout.unwrap_or(SYNCHRONOUS_REDRAW_TIMEOUT))
+                }
+                RedrawSync::RefreshRate => Some(sync.frame_duration()),
+                RedrawSync::Asynchronous => None,
+            }
+        } else if is_rescale {
+            // The window's requested size must be written before this event
+            // returns.
+            Some(sync.resize_timeout.unwrap_or_else(|| sync.frame_duration()))
+        } else {
+            sync.resize_timeout
+        };
+        if let Some(timeout) = timeout {
+            waiter.wait(timeout);
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        self.open_pinned_windows(event_loop);
+        self.pump_due_local_windows(event_loop);
+        let next_check = self.check_responsiveness(event_loop);
+        let quit_deadline = self.check_quit_timeout(event_loop);
+        let next_wake = self
+            .local_windows
+            .values()
+            .filter_map(|local| local.wake_at)
+            .chain(next_check)
+            .chain(quit_deadline)
+            .min();
+        event_loop.set_control_flow(next_wake.map_or(ControlFlow::Wait, ControlFlow::WaitUntil));
     }
 
     fn user_event(&mut self, event_loop: &ActiveEventLoop, message: EventLoopMessage<AppMessage>) {
         match message {
-            EventLoopMessage::CloseWindow(window_id) => {
-                if self.running.windows.close(window_id) {
+            EventLoopMessage::CloseWindow { window_id, reason } => {
+                if self.close_window(event_loop, window_id, reason) {
                     exit(0)
                 }
             }
-            EventLoopMessage::WindowPanic(window_id) => {
-                if self.running.windows.close(window_id) {
-                    exit(1)
+            EventLoopMessage::WindowPanic {
+                window_id,
+                payload,
+                restarts,
+                restartable,
+                restart_sender,
+            } => {
+                // Closing the window removes its route.
+                let sender = self.routes.sender(window_id);
+                self.window_panicked(
+                    event_loop,
+                    WindowPanic {
+                        id: window_id,
+                        tag: self.routes.tag(window_id),
+                        payload,
+                        restarts,
+                    },
+                    restartable,
+                    &restart_sender,
+                );
+                // The window awaits the decision without blocking, so it must
+                // be woken once the decision has been made.
+                drop(restart_sender);
+                if let Some(sender) = sender {
+                    sender.interrupt();
                 }
             }
             EventLoopMessage::OpenWindow {
@@ -332,37 +1067,52 @@ where
                 open_sender,
                 spawner,
             } => {
-                let result = self.running.windows.open(event_loop, attrs, sender);
-                if let Ok(open) = &result {
-                    spawner(open.clone());
-                }
+                let result =
+                    self.open_window(event_loop, attrs, sender, spawner, OpenedWindow::pending());
                 let _result = open_sender.send(result);
             }
+            EventLoopMessage::WakeWindow(window_id) => {
+                self.pump_local_window(event_loop, window_id);
+            }
             EventLoopMessage::User {
                 message,
                 response_sender,
             } => {
                 let _result = response_sender.send((self.message_callback)(
                     message,
-                    ExecutingApp::new(&self.running.windows, event_loop),
+                    ExecutingApp::new(&self.running, event_loop),
                 ));
             }
             EventLoopMessage::PreventShutdown => {
-                self.running.windows.prevent_shutdown();
+                self.shutdown_guards += 1;
             }
             EventLoopMessage::AllowShutdown => {
-                if self.running.windows.allow_shutdown() {
+                self.shutdown_guards -= 1;
+                if self.should_quit(event_loop) {
                     exit(0)
                 }
             }
-            EventLoopMessage::Error(err) => {
-                if let Some(handler) = &mut self.on_error {
-                    handler(err);
+            EventLoopMessage::Error { error, window } => {
+                self.report_error(error, window, ErrorPhase::Runtime);
+            }
+            EventLoopMessage::WindowInitError { window_id, error } => {
+                self.report_error(error, Some(window_id), ErrorPhase::Initialization);
+                if self.close_window(event_loop, window_id, CloseReason::InitializationFailed) {
+                    exit(0)
                 }
             }
+            EventLoopMessage::Quit => self.request_quit(),
+            EventLoopMessage::WindowResponding(window_id) => {
+                self.window_responding(event_loop, window_id);
+            }
+            EventLoopMessage::QuitResponse {
+                window_id,
+                request,
+                allowed,
+            } => self.quit_response(window_id, request, allowed),
             #[cfg(all(target_os = "linux", feature = "xdg"))]
             EventLoopMessage::ThemeChanged(theme) => {
-                self.running.windows.theme_changed(theme);
+                self.routes.theme_changed(theme);
             }
         }
     }
@@ -378,6 +1128,8 @@ where
     proxy: EventLoopProxy<EventLoopMessage<AppMessage>>,
     windows: Windows<AppMessage::Window>,
     started: Arc<AtomicBool>,
+    workers: Arc<WorkerPool<AppMessage>>,
+    event_loop_thread: ThreadId,
 }
 
 impl<AppMessage> App<AppMessage>
@@ -390,6 +1142,10 @@ where
     /// This function will return None if the main event loop is no longer
     /// running. Otherwise, this function will block until the result of the
     /// callback has been received.
+    ///
+    /// When called from the event loop thread, the message is enqueued and
+    /// None is returned, as the event loop can't handle the message until the
+    /// current callback returns.
     pub fn send(&self, message: AppMessage) -> Option<AppMessage::Response> {
         if !self.started.load(Ordering::Relaxed) {
             return None;
@@ -402,9 +1158,16 @@ where
                 response_sender,
             })
             .ok()?;
+        if self.on_event_loop_thread() {
+            return None;
+        }
         response_receiver.recv().ok()
     }
 
+    fn on_event_loop_thread(&self) -> bool {
+        thread::current().id() == self.event_loop_thread
+    }
+
     /// Sends an error to the event loop.
     ///
     /// # Errors
@@ -413,25 +1176,100 @@ where
     pub fn send_error(
         &self,
         error: AppMessage::Error,
+    ) -> Result<(), EventLoopClosed<AppMessage::Error>> {
+        self.send_error_from(error, None)
+    }
+
+    fn send_error_from(
+        &self,
+        error: AppMessage::Error,
+        window: Option<WindowId>,
     ) -> Result<(), EventLoopClosed<AppMessage::Error>> {
         if !self.started.load(Ordering::Relaxed) {
             return Err(EventLoopClosed(error));
         }
 
-        match self.proxy.send_event(EventLoopMessage::Error(error)) {
+        match self
+            .proxy
+            .send_event(EventLoopMessage::Error { error, window })
+        {
             Ok(()) => Ok(()),
-            Err(EventLoopClosed(EventLoopMessage::Error(err))) => Err(EventLoopClosed(err)),
+            Err(EventLoopClosed(EventLoopMessage::Error { error, .. })) => {
+                Err(EventLoopClosed(error))
+            }
             _ => unreachable!("returned value should be the same"),
         }
     }
 
+    /// Sends `message` to every open window.
+    ///
+    /// Returns the number of windows the message was sent to. See
+    /// [`Windows::broadcast`] for more information.
+    pub fn broadcast(&self, message: AppMessage::Window) -> usize
+    where
+        AppMessage::Window: Clone,
+    {
+        self.windows.broadcast(message)
+    }
+
+    /// Sends `message` to every open window whose id `filter` returns true
+    /// for.
+    ///
+    /// Returns the number of windows the message was sent to. See
+    /// [`Windows::broadcast_filtered`] for more information.
+    pub fn broadcast_filtered<Filter>(&self, message: AppMessage::Window, filter: Filter) -> usize
+    where
+        AppMessage::Window: Clone,
+        Filter: FnMut(WindowId) -> bool,
+    {
+        self.windows.broadcast_filtered(message, filter)
+    }
+
+    /// Sends `message` to every open window tagged with `tag`.
+    ///
+    /// Returns the number of windows the message was sent to. See
+    /// [`Windows::broadcast_tagged`] for more information.
+    pub fn broadcast_tagged(&self, tag: impl Into<WindowTag>, message: AppMessage::Window) -> usize
+    where
+        AppMessage::Window: Clone,
+    {
+        self.windows.broadcast_tagged(tag, message)
+    }
+
+    /// Requests that the app quit.
+    ///
+    /// Quitting happens in two phases. First, each open window's
+    /// [`WindowBehavior::close_requested`] is invoked. If every window allows
+    /// being closed, all windows are closed and the app exits, regardless of
+    /// the app's [`QuitPolicy`] or any [`ShutdownGuard`]s. If any window
+    /// prevents being closed, the quit is cancelled and the other windows have
+    /// [`WindowBehavior::quit_cancelled`] invoked.
+    ///
+    /// If any window has not responded within the app's
+    /// [quit timeout](PendingApp::set_quit_timeout), the
+    /// [`QuitTimeoutAction`] is applied. While a quit is pending, additional
+    /// calls to this function are ignored.
+    ///
+    /// This function does not wait for the quit to complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event loop is not currently running.
+    pub fn quit(&self) -> Result<(), EventLoopClosed<()>> {
+        self.proxy
+            .send_event(EventLoopMessage::Quit)
+            .map_err(|_| EventLoopClosed(()))
+    }
+
     /// Creates a guard that prevents this app from shutting down.
     ///
     /// If the app is not currently running, this function returns None.
     ///
-    /// Once a guard is allocated the app will not be closed automatically when
-    /// the final window is closed. If the final shutdown guard is dropped while
-    /// no windows are open, the app will be closed.
+    /// Once a guard is allocated the app will not be closed automatically, even
+    /// if the app's [`QuitPolicy`] would otherwise quit. When the final
+    /// shutdown guard is dropped, the quit policy is checked again. With the
+    /// default policy, this means the app will be closed if the final guard is
+    /// dropped while no windows are open.
     pub fn prevent_shutdown(&self) -> Option<ShutdownGuard<AppMessage>> {
         self.proxy
             .send_event(EventLoopMessage::PreventShutdown)
@@ -449,6 +1287,8 @@ where
             proxy: self.proxy.clone(),
             windows: self.windows.clone(),
             started: self.started.clone(),
+            workers: self.workers.clone(),
+            event_loop_thread: self.event_loop_thread,
         }
     }
 }
@@ -559,7 +1399,7 @@ where
     fn send(&mut self, message: AppMessage) -> Option<<AppMessage as Message>::Response> {
         Some((self.message_callback)(
             message,
-            ExecutingApp::new(&self.running.windows, &self.event_loop),
+            ExecutingApp::new(&self.running, &self.event_loop),
         ))
     }
 
@@ -568,7 +1408,14 @@ where
         error: <AppMessage as Message>::Error,
     ) -> Result<(), EventLoopClosed<<AppMessage as Message>::Error>> {
         if let Some(on_error) = &mut self.on_error {
-            on_error(error);
+            on_error(
+                error,
+                ErrorContext {
+                    window: None,
+                    tag: None,
+                    phase: ErrorPhase::Runtime,
+                },
+            );
         }
         Ok(())
     }
@@ -581,16 +1428,33 @@ where
     fn open(
         &mut self,
         window: WindowAttributes,
-        sender: Arc<mpsc::SyncSender<WindowMessage<AppMessage::Window>>>,
-        spawner: WindowSpawner,
+        sender: Arc<WindowSender<AppMessage::Window>>,
+        spawner: WindowSpawner<AppMessage>,
     ) -> Result<Option<OpenedWindow>, OsError> {
         self.pending_windows.push(PendingWindow {
             window,
             sender,
             spawner,
+            opened: OpenedWindow::pending(),
         });
         Ok(None)
     }
+
+    fn open_pinned(
+        &mut self,
+        window: WindowAttributes,
+        sender: Arc<WindowSender<AppMessage::Window>>,
+        spawner: LocalSpawner<AppMessage>,
+    ) -> Result<Option<OpenedWindow>, OsError> {
+        let opened = OpenedWindow::pending();
+        self.pending_windows.push(PendingWindow {
+            window,
+            sender,
+            spawner,
+            opened: opened.clone(),
+        });
+        Ok(Some(opened))
+    }
 }
 
 impl<AppMessage> Application<AppMessage> for App<AppMessage>
@@ -622,8 +1486,8 @@ where
     fn open(
         &mut self,
         attrs: WindowAttributes,
-        sender: Arc<mpsc::SyncSender<WindowMessage<AppMessage::Window>>>,
-        spawner: WindowSpawner,
+        sender: Arc<WindowSender<AppMessage::Window>>,
+        spawner: WindowSpawner<AppMessage>,
     ) -> Result<Option<OpenedWindow>, OsError> {
         let (open_sender, open_receiver) = mpsc::sync_channel(1);
         if self
@@ -635,37 +1499,72 @@ where
                 spawner,
             })
             .is_err()
+            || self.on_event_loop_thread()
         {
             return Ok(None);
         }
 
         open_receiver.recv().map_or(Ok(None), |opt| opt.map(Some))
     }
+
+    fn open_pinned(
+        &mut self,
+        window: WindowAttributes,
+        sender: Arc<WindowSender<AppMessage::Window>>,
+        spawner: LocalSpawner<AppMessage>,
+    ) -> Result<Option<OpenedWindow>, OsError> {
+        assert!(
+            self.on_event_loop_thread(),
+            "pinned windows must be opened on the event loop thread"
+        );
+        let opened = OpenedWindow::pending();
+        PINNED_WINDOWS.with(|pinned| {
+            pinned
+                .borrow_mut()
+                .push(Box::new(PendingWindow::<AppMessage> {
+                    window,
+                    sender,
+                    spawner,
+                    opened: opened.clone(),
+                }));
+        });
+        Ok(Some(opened))
+    }
 }
 
 /// A collection of open windows.
 pub struct Windows<Message> {
-    data: Arc<Mutex<WindowsData<Message>>>,
+    data: Arc<RwLock<WindowsData<Message>>>,
+    /// The thread the windows were created on, which runs the event loop.
+    event_loop_thread: ThreadId,
 }
 
+/// The shared view of the open windows.
+///
+/// This view is only modified when windows are opened, closed, or change
+/// focus. Events are dispatched through the event loop's [`WindowRoutes`]
+/// instead, allowing other threads to read this view without contending with
+/// event dispatch.
 struct WindowsData<Message> {
     open: HashMap<WindowId, OpenWindow<Message>>,
-    guards: usize,
+    sequence: u64,
 }
 
 impl<Message> WindowsData<Message> {
-    fn should_shutdown(&self) -> bool {
-        self.open.is_empty() && self.guards == 0
+    fn next_sequence(&mut self) -> u64 {
+        self.sequence += 1;
+        self.sequence
     }
 }
 
 impl<Message> Default for Windows<Message> {
     fn default() -> Self {
         Self {
-            data: Arc::new(Mutex::new(WindowsData {
+            data: Arc::new(RwLock::new(WindowsData {
                 open: HashMap::new(),
-                guards: 0,
+                sequence: 0,
             })),
+            event_loop_thread: thread::current().id(),
         }
     }
 }
@@ -674,6 +1573,7 @@ impl<Message> Clone for Windows<Message> {
     fn clone(&self) -> Self {
         Self {
             data: self.data.clone(),
+            event_loop_thread: self.event_loop_thread,
         }
     }
 }
@@ -682,17 +1582,104 @@ impl<Message> Windows<Message> {
     /// Gets an instance of the winit window for the given window id, if it has
     /// been opened and is still open.
     pub fn get(&self, id: WindowId) -> Option<Arc<winit::window::Window>> {
-        let windows = self.data.lock().unwrap_or_else(PoisonError::into_inner);
+        let windows = self.data.read().unwrap_or_else(PoisonError::into_inner);
         windows.open.get(&id).and_then(|w| w.winit.winit())
     }
 
+    /// Returns a handle to the window with the given id, if it has been opened
+    /// and is still open.
+    pub fn handle(&self, id: WindowId) -> Option<Window<Message>> {
+        let windows = self.data.read().unwrap_or_else(PoisonError::into_inner);
+        windows.open.get(&id).map(OpenWindow::handle)
+    }
+
+    /// Returns a handle to the first window opened with `tag` that is still
+    /// open.
+    pub fn find(&self, tag: impl Into<WindowTag>) -> Option<Window<Message>> {
+        let tag = tag.into();
+        let windows = self.data.read().unwrap_or_else(PoisonError::into_inner);
+        windows
+            .open
+            .values()
+            .filter(|window| window.tag.as_ref() == Some(&tag))
+            .min_by_key(|window| window.opened_order)
+            .map(OpenWindow::handle)
+    }
+
+    /// Returns the number of open windows.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        let windows = self.data.read().unwrap_or_else(PoisonError::into_inner);
+        windows.open.len()
+    }
+
+    /// Returns true if no windows are open.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns information about each open window, in the order the windows
+    /// were opened.
+    #[must_use]
+    pub fn open_windows(&self) -> Vec<WindowInfo<Message>> {
+        let mut windows = {
+            let data = self.data.read().unwrap_or_else(PoisonError::into_inner);
+            data.open
+                .values()
+                .map(|window| (window.opened_order, window.snapshot()))
+                .collect::<Vec<_>>()
+        };
+        windows.sort_unstable_by_key(|(order, _)| *order);
+        // Querying winit is done after the lock is released, as some platforms
+        // synchronize these calls with the event loop thread.
+        windows
+            .into_iter()
+            .filter_map(|(_, snapshot)| snapshot.into_info())
+            .collect()
+    }
+
+    /// Returns information about the window that currently has keyboard
+    /// focus, if it is one of this application's windows.
+    #[must_use]
+    pub fn focused(&self) -> Option<WindowInfo<Message>> {
+        let snapshot = {
+            let data = self.data.read().unwrap_or_else(PoisonError::into_inner);
+            data.open
+                .values()
+                .find(|window| window.focused)
+                .map(OpenWindow::snapshot)
+        };
+        snapshot.and_then(WindowSnapshot::into_info)
+    }
+
+    /// Returns information about the open window that most recently had
+    /// keyboard focus.
+    ///
+    /// If a window is currently focused, it is returned. Otherwise, the open
+    /// window that was focused last is returned. This is useful for restoring
+    /// focus after other windows have closed.
+    #[must_use]
+    pub fn last_focused(&self) -> Option<WindowInfo<Message>> {
+        let snapshot = {
+            let data = self.data.read().unwrap_or_else(PoisonError::into_inner);
+            data.open
+                .values()
+                .filter(|window| window.last_focused > 0)
+                .max_by_key(|window| window.last_focused)
+                .map(OpenWindow::snapshot)
+        };
+        snapshot.and_then(WindowSnapshot::into_info)
+    }
+
     #[allow(unsafe_code)]
     fn open(
         &self,
         target: &ActiveEventLoop,
         attrs: WindowAttributes,
-        sender: Arc<mpsc::SyncSender<WindowMessage<Message>>>,
-    ) -> Result<OpenedWindow, OsError> {
+        sender: Arc<WindowSender<Message>>,
+        opened: &OpenedWindow,
+    ) -> Result<WindowRoute<Message>, OsError> {
         let mut builder = winit::window::WindowAttributes::default()
             .with_active(attrs.active)
             .with_resizable(attrs.resizable)
@@ -743,67 +1730,575 @@ impl<Message> Windows<Message> {
         }
         let winit = Arc::new(target.create_window(builder)?);
         let id = winit.id();
-        let winit = OpenedWindow(Arc::new(Mutex::new(Some(winit))));
-        let mut windows = self.data.lock().unwrap_or_else(PoisonError::into_inner);
+        let focused = winit.has_focus();
+        let tag = attrs.tag;
+        let route = WindowRoute {
+            sender: sender.clone(),
+            tag: tag.clone(),
+            sync: WindowSync {
+                redraw: attrs.redraw_sync,
+                resize_timeout: attrs.resize_sync_timeout,
+                events: attrs.events,
+                winit: winit.clone(),
+            },
+            local: false,
+        };
+        opened.set(winit);
+        let mut windows = self.data.write().unwrap_or_else(PoisonError::into_inner);
+        let opened_order = windows.next_sequence();
+        let last_focused = if focused { windows.next_sequence() } else { 0 };
         windows.open.insert(
             id,
             OpenWindow {
-                winit: winit.clone(),
+                winit: opened.clone(),
                 sender,
+                opened_order,
+                focused,
+                last_focused,
+                tag,
             },
         );
-        Ok(winit)
+        Ok(route)
     }
 
-    fn send(&self, window: WindowId, message: WindowMessage<Message>) {
-        let mut data = self.data.lock().unwrap_or_else(PoisonError::into_inner);
-        if let Some(open_window) = data.open.get(&window) {
-            match open_window.sender.try_send(message) {
-                Ok(()) => {}
-                Err(mpsc::TrySendError::Full(_)) => {
-                    eprintln!("Dropping event for {window:?}.");
-                }
-                Err(mpsc::TrySendError::Disconnected(_)) => {
-                    // Window no longer active, remove it.
-                    data.open.remove(&window);
-                }
+    fn close(&self, window: WindowId) -> Option<OpenWindow<Message>> {
+        let mut data = self.data.write().unwrap_or_else(PoisonError::into_inner);
+        let closed = data.open.remove(&window)?;
+        closed.winit.close();
+        Some(closed)
+    }
+
+    fn focus_changed(&self, window: WindowId, focused: bool) {
+        let mut data = self.data.write().unwrap_or_else(PoisonError::into_inner);
+        let sequence = data.next_sequence();
+        if let Some(open_window) = data.open.get_mut(&window) {
+            open_window.focused = focused;
+            if focused {
+                open_window.last_focused = sequence;
             }
         }
     }
 
-    fn close(&self, window: WindowId) -> bool {
-        let mut data = self.data.lock().unwrap_or_else(PoisonError::into_inner);
-        if let Some(closed) = data.open.remove(&window) {
-            closed.winit.close();
+    /// Sends `message` to every open window.
+    ///
+    /// Returns the number of windows the message was sent to. Like
+    /// [`Window::send`], this function blocks while a window's message queue
+    /// is full. When called on the event loop thread, windows whose queues
+    /// are full are skipped instead, as blocking the event loop could prevent
+    /// those windows from ever processing their messages.
+    pub fn broadcast(&self, message: Message) -> usize
+    where
+        Message: Clone,
+    {
+        self.broadcast_filtered(message, |_| true)
+    }
+
+    /// Sends `message` to every open window whose id `filter` returns true
+    /// for.
+    ///
+    /// Returns the number of windows the message was sent to. See
+    /// [`broadcast()`](Self::broadcast) for how full message queues are
+    /// handled.
+    pub fn broadcast_filtered<Filter>(&self, message: Message, filter: Filter) -> usize
+    where
+        Message: Clone,
+        Filter: FnMut(WindowId) -> bool,
+    {
+        let mut filter = filter;
+        self.broadcast_with(|id, _| filter(id), || WindowMessage::User(message.clone()))
+    }
+
+    /// Sends `message` to every open window tagged with `tag`.
+    ///
+    /// Returns the number of windows the message was sent to. See
+    /// [`broadcast()`](Self::broadcast) for how full message queues are
+    /// handled.
+    pub fn broadcast_tagged(&self, tag: impl Into<WindowTag>, message: Message) -> usize
+    where
+        Message: Clone,
+    {
+        let tag = tag.into();
+        self.broadcast_with(
+            |_, window| window.tag.as_ref() == Some(&tag),
+            || WindowMessage::User(message.clone()),
+        )
+    }
+
+    fn broadcast_with(
+        &self,
+        mut filter: impl FnMut(WindowId, &OpenWindow<Message>) -> bool,
+        mut message: impl FnMut() -> WindowMessage<Message>,
+    ) -> usize {
+        // Collect the senders before sending to avoid holding the lock while
+        // blocking on a full channel.
+        let on_event_loop = thread::current().id() == self.event_loop_thread;
+        let senders = {
+            let data = self.data.read().unwrap_or_else(PoisonError::into_inner);
+            data.open
+                .iter()
+                .filter(|(id, window)| filter(**id, window))
+                .map(|(_, window)| window.sender.clone())
+                .collect::<Vec<_>>()
+        };
+        senders
+            .into_iter()
+            .filter(|sender| {
+                if on_event_loop {
+                    sender.try_send(message()).is_ok()
+                } else {
+                    sender.send(message()).is_ok()
+                }
+            })
+            .count()
+    }
+}
+
+struct OpenWindow<User> {
+    winit: OpenedWindow,
+    sender: Arc<WindowSender<User>>,
+    opened_order: u64,
+    focused: bool,
+    last_focused: u64,
+    tag: Option<WindowTag>,
+}
+
+/// The event loop's table of open windows.
+///
+/// Every window event is dispatched through this table. Because it is owned
+/// by the event loop, dispatching events does not take the lock that guards
+/// the [`Windows`] view shared with other threads.
+///
+/// `S` is the state the event loop keeps to synchronize with each window. It
+/// is only replaced when benchmarking dispatch without real windows.
+struct WindowRoutes<Message, S = WindowSync> {
+    routes: HashMap<WindowId, WindowRoute<Message, S>>,
+}
+
+impl<Message, S> Default for WindowRoutes<Message, S> {
+    fn default() -> Self {
+        Self {
+            routes: HashMap::new(),
         }
-        data.should_shutdown()
+    }
+}
+
+impl<Message, S> WindowRoutes<Message, S> {
+    fn insert(&mut self, id: WindowId, route: WindowRoute<Message, S>) {
+        self.routes.insert(id, route);
+    }
+
+    fn remove(&mut self, id: WindowId) {
+        self.routes.remove(&id);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.routes.is_empty()
     }
 
-    fn prevent_shutdown(&self) {
-        let mut data = self.data.lock().unwrap_or_else(PoisonError::into_inner);
-        data.guards += 1;
+    fn contains(&self, window: WindowId) -> bool {
+        self.routes.contains_key(&window)
     }
 
-    fn allow_shutdown(&self) -> bool {
-        let mut data = self.data.lock().unwrap_or_else(PoisonError::into_inner);
-        data.guards -= 1;
-        data.should_shutdown()
+    fn ids(&self) -> Vec<WindowId> {
+        self.routes.keys().copied().collect()
+    }
+
+    fn tag(&self, window: WindowId) -> Option<WindowTag> {
+        self.routes.get(&window).and_then(|route| route.tag.clone())
+    }
+
+    fn sender(&self, window: WindowId) -> Option<Arc<WindowSender<Message>>> {
+        self.routes.get(&window).map(|route| route.sender.clone())
+    }
+
+    /// Sends `message` to `window` without blocking, returning true if the
+    /// message was enqueued.
+    fn send(&self, window: WindowId, message: WindowMessage<Message>) -> bool {
+        let Some(route) = self.routes.get(&window) else {
+            return false;
+        };
+        // If the window's thread has exited, it will notify the event loop
+        // that it has closed, which will remove it.
+        match route.sender.try_send(message) {
+            Ok(()) => true,
+            Err(mpsc::TrySendError::Full(_)) => {
+                eprintln!("Dropping event for {window:?}.");
+                false
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => false,
+        }
+    }
+
+    /// Flags windows whose messages have been waiting for longer than
+    /// `threshold`.
+    ///
+    /// Returns the ids of windows that were newly flagged along with how long
+    /// they have been unresponsive, and the next time a window with waiting
+    /// messages could exceed the threshold.
+    fn unresponsive(
+        &self,
+        now: Instant,
+        threshold: Duration,
+    ) -> (Vec<(WindowId, Duration)>, Option<Instant>) {
+        let mut unresponsive = Vec::new();
+        let mut next_check = None::<Instant>;
+        for (id, route) in &self.routes {
+            // Local windows block the event loop while they are unresponsive.
+            if route.local {
+                continue;
+            }
+            let Some(waiting_since) = route.sender.waiting_since() else {
+                continue;
+            };
+            let deadline = waiting_since + threshold;
+            if deadline > now {
+                next_check = Some(next_check.map_or(deadline, |next| next.min(deadline)));
+            } else if route.sender.set_unresponsive() {
+                unresponsive.push((*id, now.saturating_duration_since(waiting_since)));
+            }
+        }
+        (unresponsive, next_check)
+    }
+
+    /// Returns false if `window` has been flagged as not responding.
+    fn responding(&self, window: WindowId) -> bool {
+        self.routes
+            .get(&window)
+            .map_or(true, |route| route.sender.responding())
     }
 
     #[cfg(all(target_os = "linux", feature = "xdg"))]
     fn theme_changed(&self, theme: winit::window::Theme) {
-        let data = self.data.lock().unwrap_or_else(PoisonError::into_inner);
-        for window in data.open.values() {
-            let _ = window
-                .sender
-                .send(WindowMessage::Event(WindowEvent::ThemeChanged(theme)));
+        let received = Instant::now();
+        for route in self.routes.values() {
+            let _result = route.sender.send(WindowMessage::Event(
+                WindowEvent::ThemeChanged(theme),
+                received,
+            ));
         }
     }
 }
 
-struct OpenWindow<User> {
-    winit: OpenedWindow,
-    sender: Arc<mpsc::SyncSender<WindowMessage<User>>>,
+impl<Message> WindowRoutes<Message> {
+    fn sync(&self, window: WindowId) -> Option<WindowSync> {
+        self.routes.get(&window).map(|route| route.sync.clone())
+    }
+
+    /// Disconnects an unresponsive window from its queue and hides it.
+    ///
+    /// The window's thread cannot be stopped, so its behavior exits the next
+    /// time it processes its messages.
+    fn force_close(&self, window: WindowId) {
+        if let Some(route) = self.routes.get(&window) {
+            route.sender.force_close();
+            route.sync.winit.set_visible(false);
+        }
+    }
+}
+
+struct WindowRoute<Message, S = WindowSync> {
+    sender: Arc<WindowSender<Message>>,
+    tag: Option<WindowTag>,
+    sync: S,
+    /// True if the window's behavior is driven by the event loop.
+    local: bool,
+}
+
+/// How the event loop synchronizes with a window's redraws.
+#[derive(Clone)]
+struct WindowSync {
+    redraw: RedrawSync,
+    resize_timeout: Option<Duration>,
+    events: EventCategories,
+    winit: Arc<winit::window::Window>,
+}
+
+impl WindowSync {
+    fn frame_duration(&self) -> Duration {
+        RedrawSync::frame_duration(&self.winit)
+    }
+}
+
+impl<User> OpenWindow<User> {
+    fn handle(&self) -> Window<User> {
+        Window::new(self.winit.clone(), &self.sender)
+    }
+
+    fn snapshot(&self) -> WindowSnapshot<User> {
+        WindowSnapshot {
+            handle: self.handle(),
+            focused: self.focused,
+            tag: self.tag.clone(),
+        }
+    }
+}
+
+struct WindowSnapshot<User> {
+    handle: Window<User>,
+    focused: bool,
+    tag: Option<WindowTag>,
+}
+
+impl<User> WindowSnapshot<User> {
+    fn into_info(self) -> Option<WindowInfo<User>> {
+        let winit = self.handle.opened.winit()?;
+        Some(WindowInfo {
+            id: winit.id(),
+            title: winit.title(),
+            focused: self.focused,
+            tag: self.tag,
+            handle: self.handle,
+        })
+    }
+}
+
+/// Information about a window that has been opened.
+#[derive(Debug, Clone)]
+pub struct WindowOpened {
+    /// The winit id of the window.
+    pub id: WindowId,
+    /// The tag the window was opened with.
+    pub tag: Option<WindowTag>,
+}
+
+/// Information about a pinned window that failed to open.
+#[derive(Debug)]
+pub struct WindowOpenFailed {
+    /// The tag the window would have been opened with.
+    pub tag: Option<WindowTag>,
+    /// The error that prevented the window from opening.
+    pub error: OpenError,
+}
+
+/// Information about a window that has been closed.
+#[derive(Debug, Clone)]
+pub struct WindowClosed {
+    /// The winit id of the window.
+    pub id: WindowId,
+    /// The tag the window was opened with.
+    pub tag: Option<WindowTag>,
+    /// The reason the window was closed.
+    pub reason: CloseReason,
+}
+
+/// Information about a window whose behavior panicked.
+#[derive(Debug)]
+pub struct WindowPanic {
+    /// The winit id of the window.
+    pub id: WindowId,
+    /// The tag the window was opened with.
+    pub tag: Option<WindowTag>,
+    /// The value the behavior panicked with.
+    pub payload: Box<dyn Any + Send>,
+    /// The number of times this window has been restarted due to previous
+    /// panics.
+    pub restarts: u32,
+}
+
+impl WindowPanic {
+    /// Returns the panic's message, if the payload is a string.
+    #[must_use]
+    pub fn message(&self) -> Option<&str> {
+        self.payload
+            .downcast_ref::<&'static str>()
+            .copied()
+            .or_else(|| self.payload.downcast_ref::<String>().map(String::as_str))
+    }
+}
+
+/// Information about a window that has stopped responding.
+#[derive(Debug, Clone)]
+pub struct WindowUnresponsive {
+    /// The winit id of the window.
+    pub id: WindowId,
+    /// The tag the window was opened with.
+    pub tag: Option<WindowTag>,
+    /// How long the window has had messages waiting without processing any of
+    /// them.
+    pub unresponsive_for: Duration,
+}
+
+/// Information about a window that is responding again after it was detected
+/// as not responding.
+#[derive(Debug, Clone)]
+pub struct WindowResponsive {
+    /// The winit id of the window.
+    pub id: WindowId,
+    /// The tag the window was opened with.
+    pub tag: Option<WindowTag>,
+}
+
+/// The action to take when a window stops responding.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum UnresponsiveAction {
+    /// Keep the window open. The window is considered responding again once
+    /// it processes another message. This is the default.
+    #[default]
+    Wait,
+    /// Close the window without waiting for its behavior.
+    ///
+    /// The window is hidden and removed from the app immediately, and
+    /// [`PendingApp::on_window_closed`] is notified with
+    /// [`CloseReason::Unresponsive`]. Because its thread cannot be stopped,
+    /// the behavior is dropped if it ever resumes processing messages.
+    ForceClose,
+}
+
+/// A policy that determines how an app responds to a window's behavior
+/// panicking.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum PanicPolicy {
+    /// Close the window. If closing the window causes the app to quit, the
+    /// process exits with a non-zero exit code. This is the default policy.
+    #[default]
+    CloseWindow,
+    /// Restart the window by initializing a new behavior.
+    ///
+    /// Only windows opened with [`WindowBuilder::restart_with`] can be
+    /// restarted. Other windows, and windows that have already been restarted
+    /// `max_restarts` times, are closed.
+    Restart {
+        /// The maximum number of times a window will be restarted.
+        max_restarts: u32,
+        /// The delay before the first restart. The delay doubles for each
+        /// subsequent restart.
+        backoff: Duration,
+    },
+    /// Exit the process with a non-zero exit code.
+    Exit,
+}
+
+/// Information about where an error reported to [`PendingApp::on_error`]
+/// originated.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ErrorContext {
+    /// The id of the window the error originated from, if the error was
+    /// reported by a window.
+    pub window: Option<WindowId>,
+    /// The tag of the window the error originated from.
+    pub tag: Option<WindowTag>,
+    /// The phase the error occurred during.
+    pub phase: ErrorPhase,
+}
+
+/// The phase of a window's lifecycle an error occurred during.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ErrorPhase {
+    /// The error was returned from [`WindowBehavior::initialize`]. The window
+    /// is closed after the error is handled.
+    Initialization,
+    /// The error was reported while the app or window was running, such as
+    /// through [`Application::send_error`] or by returning an error from
+    /// [`WindowBehavior::try_redraw`].
+    Runtime,
+}
+
+/// The reason a window was closed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CloseReason {
+    /// The user requested the window be closed, and
+    /// [`WindowBehavior::close_requested`] allowed it.
+    UserRequested,
+    /// The window was closed using [`RunningWindow::close`], or the underlying
+    /// window was destroyed.
+    Programmatic,
+    /// The window's behavior panicked.
+    Panic,
+    /// [`WindowBehavior::initialize`] returned an error.
+    InitializationFailed,
+    /// The app quit using [`App::quit`].
+    Quit,
+    /// A fallible [`WindowBehavior`] callback returned an error, and the
+    /// window's [`RuntimeErrorAction`] was
+    /// [`CloseWindow`](RuntimeErrorAction::CloseWindow).
+    RuntimeError,
+    /// The window stopped responding, and either the handler installed with
+    /// [`PendingApp::on_window_unresponsive`] returned
+    /// [`UnresponsiveAction::ForceClose`] or the window did not respond to
+    /// [`App::quit`] before the quit timeout with [`QuitTimeoutAction::Quit`].
+    Unresponsive,
+}
+
+/// Information about an open window.
+#[derive(Debug)]
+pub struct WindowInfo<Message> {
+    /// The winit id of the window.
+    pub id: WindowId,
+    /// A handle to the window.
+    pub handle: Window<Message>,
+    /// The title of the window.
+    pub title: String,
+    /// If true, this window currently has keyboard focus.
+    pub focused: bool,
+    /// The tag the window was opened with.
+    pub tag: Option<WindowTag>,
+}
+
+/// The action taken when windows have not responded to [`App::quit`] within
+/// the app's [quit timeout](PendingApp::set_quit_timeout).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum QuitTimeoutAction {
+    /// Cancel the quit, as if the windows that did not respond had refused.
+    /// This is the default.
+    #[default]
+    Cancel,
+    /// Quit as if the windows that did not respond had agreed.
+    ///
+    /// Windows that did not respond may never process a request to close, so
+    /// they are forcibly closed with [`CloseReason::Unresponsive`]. The other
+    /// windows are asked to close, and the app exits once all windows have
+    /// closed.
+    Quit,
+}
+
+/// A policy that determines when an app automatically quits.
+///
+/// The policy is checked each time a window is closed and each time a
+/// [`ShutdownGuard`] is dropped. The app never quits automatically while a
+/// [`ShutdownGuard`] exists.
+pub enum QuitPolicy<AppMessage>
+where
+    AppMessage: Message,
+{
+    /// Quit once no windows are open. This is the default policy.
+    LastWindowClosed,
+    /// Quit once a window with the given tag has been closed.
+    MainWindowClosed(WindowTag),
+    /// Never quit automatically.
+    ///
+    /// This is useful for apps that continue running without any open
+    /// windows, such as apps with a tray icon.
+    Never,
+    /// Invoke a callback to determine whether to quit. If the callback returns
+    /// true, the app will quit.
+    Custom(BoxedQuitCallback<AppMessage>),
+}
+
+type BoxedQuitCallback<AppMessage> = Box<dyn FnMut(ExecutingApp<'_, AppMessage>) -> bool>;
+
+impl<AppMessage> QuitPolicy<AppMessage>
+where
+    AppMessage: Message,
+{
+    /// Returns a [`QuitPolicy::Custom`] policy that invokes `should_quit` to
+    /// determine whether to quit.
+    pub fn custom<F>(should_quit: F) -> Self
+    where
+        F: FnMut(ExecutingApp<'_, AppMessage>) -> bool + 'static,
+    {
+        Self::Custom(Box::new(should_quit))
+    }
+}
+
+// Deriving Default would require `AppMessage: Default`.
+#[allow(clippy::derivable_impls)]
+impl<AppMessage> Default for QuitPolicy<AppMessage>
+where
+    AppMessage: Message,
+{
+    fn default() -> Self {
+        Self::LastWindowClosed
+    }
 }
 
 /// A guard preventing an [`App`] from shutting down.
@@ -822,3 +2317,55 @@ where
         let _ = self.app.proxy.send_event(EventLoopMessage::AllowShutdown);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{mpsc, Arc, PoisonError};
+    use std::thread;
+    use std::time::Duration;
+
+    use winit::window::WindowId;
+
+    use crate::private::{OpenedWindow, WindowMessage, WindowReceiver, WindowSender};
+    use crate::{OpenWindow, Windows};
+
+    fn open(windows: &Windows<u32>, id: u64) -> (Arc<WindowSender<u32>>, WindowReceiver<u32>) {
+        let (sender, receiver) = WindowSender::queue();
+        let sender = Arc::new(sender);
+        let mut data = windows.data.write().unwrap_or_else(PoisonError::into_inner);
+        let opened_order = data.next_sequence();
+        data.open.insert(
+            WindowId::from(id),
+            OpenWindow {
+                winit: OpenedWindow::pending(),
+                sender: sender.clone(),
+                opened_order,
+                focused: false,
+                last_focused: 0,
+                tag: None,
+            },
+        );
+        (sender, receiver)
+    }
+
+    #[test]
+    fn broadcasting_on_the_event_loop_skips_full_windows() {
+        let (done, finished) = mpsc::channel();
+        // The windows are created on this thread, which acts as the event
+        // loop thread.
+        thread::spawn(move || {
+            let windows = Windows::default();
+            let (full, _full_receiver) = open(&windows, 0);
+            let (_open, open_receiver) = open(&windows, 1);
+            while full.try_send(WindowMessage::User(0)).is_ok() {}
+
+            let sent = windows.broadcast(1);
+            done.send((sent, open_receiver.try_recv())).unwrap();
+        });
+        let (sent, received) = finished
+            .recv_timeout(Duration::from_secs(5))
+            .expect("broadcast blocked the event loop thread");
+        assert_eq!(sent, 1);
+        assert!(matches!(received, Some(WindowMessage::User(1))));
+    }
+}
@@ -0,0 +1,250 @@
+use std::io;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::Instant;
+
+use winit::event_loop::EventLoopProxy;
+
+use crate::private::{self, DriveWindow, EventLoopMessage, Pumped, WindowSender};
+use crate::Message;
+
+/// Creates a window's driver on the worker thread that will drive it.
+pub type SpawnDriver<AppMessage> = Box<dyn FnOnce() -> Box<dyn DriveWindow<AppMessage>> + Send>;
+
+/// A pool of threads that drive windows opened with
+/// [`ExecutionMode::Shared`](crate::ExecutionMode::Shared).
+///
+/// Worker threads are spawned as windows are assigned to the pool, up to the
+/// maximum number of workers.
+pub struct WorkerPool<AppMessage>
+where
+    AppMessage: Message,
+{
+    state: Mutex<PoolState<AppMessage>>,
+}
+
+struct PoolState<AppMessage>
+where
+    AppMessage: Message,
+{
+    proxy: EventLoopProxy<EventLoopMessage<AppMessage>>,
+    max_workers: usize,
+    workers: Vec<Arc<Worker<AppMessage>>>,
+}
+
+impl<AppMessage> WorkerPool<AppMessage>
+where
+    AppMessage: Message,
+{
+    pub fn new(proxy: EventLoopProxy<EventLoopMessage<AppMessage>>) -> Self {
+        Self {
+            state: Mutex::new(PoolState {
+                proxy,
+                max_workers: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+                workers: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn set_max_workers(&self, max_workers: usize) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.max_workers = max_workers.max(1);
+    }
+
+    /// Reserves a worker for a window that is about to be opened.
+    ///
+    /// The worker is chosen, and spawned if needed, before the window is
+    /// opened so that failing to spawn a thread can be reported to the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a new worker thread could not be spawned.
+    pub fn reserve(&self) -> io::Result<Reservation<AppMessage>> {
+        let worker = self
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .least_busy_worker()?;
+        worker.windows.fetch_add(1, Ordering::Relaxed);
+        Ok(Reservation {
+            worker,
+            assigned: false,
+        })
+    }
+}
+
+/// A worker reserved to drive a window.
+///
+/// Dropping the reservation without assigning a window to it releases the
+/// worker.
+pub struct Reservation<AppMessage>
+where
+    AppMessage: Message,
+{
+    worker: Arc<Worker<AppMessage>>,
+    assigned: bool,
+}
+
+impl<AppMessage> Reservation<AppMessage>
+where
+    AppMessage: Message,
+{
+    /// Assigns a window to the reserved worker, which will create its driver
+    /// using `spawn` and drive it until the window exits.
+    pub fn assign<User>(mut self, sender: &WindowSender<User>, spawn: SpawnDriver<AppMessage>) {
+        self.assigned = true;
+        sender.set_waker({
+            let worker = self.worker.clone();
+            move || worker.notify()
+        });
+        self.worker.assign(spawn);
+    }
+}
+
+impl<AppMessage> Drop for Reservation<AppMessage>
+where
+    AppMessage: Message,
+{
+    fn drop(&mut self) {
+        if !self.assigned {
+            self.worker.windows.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<AppMessage> PoolState<AppMessage>
+where
+    AppMessage: Message,
+{
+    fn least_busy_worker(&mut self) -> io::Result<Arc<Worker<AppMessage>>> {
+        let least_busy = self
+            .workers
+            .iter()
+            .min_by_key(|worker| worker.windows.load(Ordering::Relaxed));
+        match least_busy {
+            Some(worker)
+                if worker.windows.load(Ordering::Relaxed) == 0
+                    || self.workers.len() >= self.max_workers =>
+            {
+                Ok(worker.clone())
+            }
+            _ => {
+                let worker = Arc::new(Worker {
+                    queue: Mutex::new(WorkerQueue {
+                        incoming: Vec::new(),
+                        notified: false,
+                    }),
+                    wake: Condvar::new(),
+                    windows: AtomicUsize::new(0),
+                });
+                thread::Builder::new()
+                    .name(format!("appit worker {}", self.workers.len()))
+                    .spawn({
+                        let worker = worker.clone();
+                        let proxy = self.proxy.clone();
+                        move || worker.run(&proxy)
+                    })?;
+                self.workers.push(worker.clone());
+                Ok(worker)
+            }
+        }
+    }
+}
+
+struct Worker<AppMessage>
+where
+    AppMessage: Message,
+{
+    queue: Mutex<WorkerQueue<AppMessage>>,
+    wake: Condvar,
+    windows: AtomicUsize,
+}
+
+struct WorkerQueue<AppMessage>
+where
+    AppMessage: Message,
+{
+    incoming: Vec<SpawnDriver<AppMessage>>,
+    notified: bool,
+}
+
+struct PooledWindow<AppMessage>
+where
+    AppMessage: Message,
+{
+    driver: Box<dyn DriveWindow<AppMessage>>,
+    wake_at: Option<Instant>,
+}
+
+impl<AppMessage> Worker<AppMessage>
+where
+    AppMessage: Message,
+{
+    fn assign(&self, spawn: SpawnDriver<AppMessage>) {
+        let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+        queue.incoming.push(spawn);
+        queue.notified = true;
+        self.wake.notify_one();
+    }
+
+    fn notify(&self) {
+        let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+        queue.notified = true;
+        self.wake.notify_one();
+    }
+
+    fn run(&self, proxy: &EventLoopProxy<EventLoopMessage<AppMessage>>) {
+        let mut windows = Vec::<PooledWindow<AppMessage>>::new();
+        loop {
+            let incoming = {
+                let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+                queue.notified = false;
+                std::mem::take(&mut queue.incoming)
+            };
+            windows.extend(incoming.into_iter().map(|spawn| PooledWindow {
+                driver: spawn(),
+                wake_at: Some(Instant::now()),
+            }));
+
+            let now = Instant::now();
+            let mut index = 0;
+            while index < windows.len() {
+                let window = &mut windows[index];
+                if private::pump_due(&*window.driver, window.wake_at, now) {
+                    match private::pump_threaded(&mut *window.driver, |message| {
+                        proxy.send_event(message).is_ok()
+                    }) {
+                        Pumped::Wait(wake_at) => window.wake_at = wake_at,
+                        Pumped::Exited(message) => {
+                            // The driver must be dropped before the event loop
+                            // is notified that the window has closed.
+                            drop(windows.swap_remove(index));
+                            self.windows.fetch_sub(1, Ordering::Relaxed);
+                            if let Some(message) = message {
+                                let _result = proxy.send_event(message);
+                            }
+                            continue;
+                        }
+                    }
+                }
+                index += 1;
+            }
+
+            let next_wake = windows.iter().filter_map(|window| window.wake_at).min();
+            let queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+            if queue.notified {
+                continue;
+            }
+            match next_wake {
+                Some(at) => {
+                    if let Some(timeout) = at.checked_duration_since(Instant::now()) {
+                        drop(self.wake.wait_timeout(queue, timeout));
+                    }
+                }
+                None => drop(self.wake.wait(queue)),
+            }
+        }
+    }
+}
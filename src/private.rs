@@ -1,20 +1,32 @@
+use std::any::Any;
+use std::collections::VecDeque;
 use std::path::PathBuf;
-use std::sync::{mpsc, Arc, Mutex, PoisonError};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::error::OsError;
 use winit::event::{
-    AxisId, DeviceId, ElementState, Ime, KeyEvent, Modifiers, MouseButton, MouseScrollDelta, Touch,
-    TouchPhase,
+    AxisId, DeviceId, ElementState, Ime, InnerSizeWriter, KeyEvent, Modifiers, MouseButton,
+    MouseScrollDelta, Touch, TouchPhase,
 };
 use winit::event_loop::AsyncRequestSerial;
 use winit::window::{ActivationToken, Theme, WindowId};
 
 use crate::window::WindowAttributes;
-use crate::Message;
+use crate::{CloseReason, Message};
 
-pub type WindowSpawner = Box<dyn FnOnce(OpenedWindow) + Send + 'static>;
+/// Starts running a window's behavior once the window has been opened.
+///
+/// If the behavior should be driven by the event loop, the window's driver is
+/// returned.
+pub type WindowSpawner<AppMessage> =
+    Box<dyn FnOnce(OpenedWindow) -> Option<Box<dyn DriveWindow<AppMessage>>> + Send + 'static>;
+
+/// A [`WindowSpawner`] that must be invoked on the event loop thread.
+pub type LocalSpawner<AppMessage> =
+    Box<dyn FnOnce(OpenedWindow) -> Option<Box<dyn DriveWindow<AppMessage>>> + 'static>;
 
 pub trait ApplicationSealed<AppMessage>
 where
@@ -23,15 +35,548 @@ where
     fn open(
         &mut self,
         window: WindowAttributes,
-        sender: Arc<mpsc::SyncSender<WindowMessage<AppMessage::Window>>>,
-        spawner: WindowSpawner,
+        sender: Arc<WindowSender<AppMessage::Window>>,
+        spawner: WindowSpawner<AppMessage>,
+    ) -> Result<Option<OpenedWindow>, OsError>;
+
+    fn open_pinned(
+        &mut self,
+        window: WindowAttributes,
+        sender: Arc<WindowSender<AppMessage::Window>>,
+        spawner: LocalSpawner<AppMessage>,
     ) -> Result<Option<OpenedWindow>, OsError>;
 }
 
+/// The number of messages each lane of a window's queue can hold.
+///
+/// The window's thread shouldn't ever block for long periods of time. To avoid
+/// a "frozen" window causing massive memory allocations, the lanes are
+/// bounded, and the event loop is careful to never block when sending.
+const LANE_CAPACITY: usize = 65536;
+
+/// A window's message queue.
+///
+/// Messages are split into two lanes. Control messages, such as redraw
+/// requests, resizes, and close requests, are always received before input
+/// events and user messages. This keeps a window that is behind on processing
+/// input responsive to lifecycle events.
+struct MessageQueue<User> {
+    lanes: Mutex<Lanes<User>>,
+    received: Condvar,
+    space: Condvar,
+    unresponsive: AtomicBool,
+}
+
+struct Lanes<User> {
+    control: VecDeque<WindowMessage<User>>,
+    input: VecDeque<WindowMessage<User>>,
+    /// The time the window was last known to be making progress on its queued
+    /// messages, or `None` if the queue is empty.
+    waiting_since: Option<Instant>,
+    /// True while the window's behavior is waiting to be restarted after a
+    /// panic. Messages are not processed while restarting, so the window is
+    /// not considered waiting.
+    restarting: bool,
+    /// True if the window has been interrupted since it last waited.
+    interrupted: bool,
+    disconnected: bool,
+}
+
+impl<User> Lanes<User> {
+    fn lane(&mut self, control: bool) -> &mut VecDeque<WindowMessage<User>> {
+        if control {
+            &mut self.control
+        } else {
+            &mut self.input
+        }
+    }
+
+    fn push(&mut self, control: bool, message: WindowMessage<User>) {
+        self.lane(control).push_back(message);
+        self.waiting_since.get_or_insert_with(Instant::now);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.control.is_empty() && self.input.is_empty()
+    }
+
+    /// Disconnects the queue, returning the messages that were queued.
+    fn disconnect(&mut self) -> [VecDeque<WindowMessage<User>>; 2] {
+        self.disconnected = true;
+        self.waiting_since = None;
+        [
+            std::mem::take(&mut self.control),
+            std::mem::take(&mut self.input),
+        ]
+    }
+}
+
+/// The sending half of a window's message queue.
+///
+/// Windows that aren't running on a dedicated thread install a waker, which is
+/// invoked when a message is sent to schedule the window to process its
+/// messages.
+pub struct WindowSender<User> {
+    queue: Arc<MessageQueue<User>>,
+    woken: AtomicBool,
+    waker: Mutex<Option<Box<dyn Fn() + Send>>>,
+}
+
+impl<User> WindowSender<User> {
+    /// Returns a new message queue for a window.
+    pub fn queue() -> (Self, WindowReceiver<User>) {
+        let queue = Arc::new(MessageQueue {
+            lanes: Mutex::new(Lanes {
+                control: VecDeque::new(),
+                input: VecDeque::new(),
+                waiting_since: None,
+                restarting: false,
+                interrupted: false,
+                disconnected: false,
+            }),
+            received: Condvar::new(),
+            space: Condvar::new(),
+            unresponsive: AtomicBool::new(false),
+        });
+        (
+            Self {
+                queue: queue.clone(),
+                woken: AtomicBool::new(false),
+                waker: Mutex::new(None),
+            },
+            WindowReceiver(queue),
+        )
+    }
+
+    pub fn set_waker(&self, waker: impl Fn() + Send + 'static) {
+        *self.waker.lock().unwrap_or_else(PoisonError::into_inner) = Some(Box::new(waker));
+    }
+
+    /// Sends `message`, blocking while its lane is full.
+    #[allow(clippy::result_large_err)] // mirrors mpsc::SyncSender
+    pub fn send(
+        &self,
+        message: WindowMessage<User>,
+    ) -> Result<(), mpsc::SendError<WindowMessage<User>>> {
+        self.send_until(message, None).map_err(|err| match err {
+            mpsc::TrySendError::Full(message) | mpsc::TrySendError::Disconnected(message) => {
+                mpsc::SendError(message)
+            }
+        })
+    }
+
+    /// Sends `message`, blocking for up to `timeout` while its lane is full.
+    #[allow(clippy::result_large_err)] // mirrors mpsc::SyncSender
+    pub fn send_timeout(
+        &self,
+        message: WindowMessage<User>,
+        timeout: Duration,
+    ) -> Result<(), mpsc::TrySendError<WindowMessage<User>>> {
+        self.send_until(message, Instant::now().checked_add(timeout))
+    }
+
+    #[allow(clippy::result_large_err)] // mirrors mpsc::SyncSender
+    fn send_until(
+        &self,
+        message: WindowMessage<User>,
+        deadline: Option<Instant>,
+    ) -> Result<(), mpsc::TrySendError<WindowMessage<User>>> {
+        let control = message.is_control();
+        let mut lanes = self
+            .queue
+            .lanes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        loop {
+            if lanes.disconnected {
+                return Err(mpsc::TrySendError::Disconnected(message));
+            }
+            if lanes.lane(control).len() < LANE_CAPACITY {
+                lanes.push(control, message);
+                break;
+            }
+            lanes = match deadline {
+                Some(deadline) => {
+                    let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                        return Err(mpsc::TrySendError::Full(message));
+                    };
+                    self.queue
+                        .space
+                        .wait_timeout(lanes, remaining)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => self
+                    .queue
+                    .space
+                    .wait(lanes)
+                    .unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+        drop(lanes);
+        self.queue.received.notify_one();
+        self.wake();
+        Ok(())
+    }
+
+    /// Sends `message` without blocking.
+    #[allow(clippy::result_large_err)] // mirrors mpsc::SyncSender
+    pub fn try_send(
+        &self,
+        message: WindowMessage<User>,
+    ) -> Result<(), mpsc::TrySendError<WindowMessage<User>>> {
+        let mut lanes = self
+            .queue
+            .lanes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if lanes.disconnected {
+            return Err(mpsc::TrySendError::Disconnected(message));
+        }
+        let control = message.is_control();
+        if lanes.lane(control).len() >= LANE_CAPACITY {
+            return Err(mpsc::TrySendError::Full(message));
+        }
+        lanes.push(control, message);
+        drop(lanes);
+        self.queue.received.notify_one();
+        self.wake();
+        Ok(())
+    }
+
+    /// Enqueues `message` regardless of its lane's capacity, without waking
+    /// the window.
+    ///
+    /// This is used by the event loop to deliver events to windows that it
+    /// pumps itself.
+    pub fn deliver(&self, message: WindowMessage<User>) {
+        let mut lanes = self
+            .queue
+            .lanes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if !lanes.disconnected {
+            lanes.push(message.is_control(), message);
+        }
+    }
+
+    /// Returns the time the window was last known to be making progress on
+    /// its queued messages, or `None` if no messages are queued or the
+    /// window is restarting.
+    pub fn waiting_since(&self) -> Option<Instant> {
+        let lanes = self
+            .queue
+            .lanes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        lanes.waiting_since.filter(|_| !lanes.restarting)
+    }
+
+    /// Sets whether the window's behavior is waiting to be restarted.
+    ///
+    /// Once the restart completes, queued messages are considered waiting
+    /// from that point on.
+    pub fn set_restarting(&self, restarting: bool) {
+        let mut lanes = self
+            .queue
+            .lanes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        lanes.restarting = restarting;
+        if !restarting {
+            lanes.waiting_since = (!lanes.is_empty()).then(Instant::now);
+        }
+    }
+
+    /// Flags the window as not responding, returning true if the window was
+    /// previously responding.
+    pub fn set_unresponsive(&self) -> bool {
+        !self.queue.unresponsive.swap(true, Ordering::AcqRel)
+    }
+
+    /// Clears the not responding flag, returning true if the window was
+    /// previously flagged. Called each time the window receives a message.
+    pub fn set_responding(&self) -> bool {
+        // Avoid writing to the shared flag for every message.
+        self.queue.unresponsive.load(Ordering::Acquire)
+            && self.queue.unresponsive.swap(false, Ordering::AcqRel)
+    }
+
+    pub fn responding(&self) -> bool {
+        !self.queue.unresponsive.load(Ordering::Acquire)
+    }
+
+    /// Disconnects the queue, discarding queued messages. The window exits
+    /// the next time it processes its messages.
+    pub fn force_close(&self) {
+        let mut lanes = self
+            .queue
+            .lanes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let discarded = lanes.disconnect();
+        drop(lanes);
+        self.queue.received.notify_all();
+        self.queue.space.notify_all();
+        drop(discarded);
+    }
+
+    /// Returns true if a message has been sent since the last call to
+    /// [`Self::clear_woken`].
+    pub fn woken(&self) -> bool {
+        self.woken.load(Ordering::Acquire)
+    }
+
+    pub fn clear_woken(&self) {
+        self.woken.store(false, Ordering::Release);
+    }
+
+    /// Wakes the window without sending it a message, so that it is pumped
+    /// again even if its queue is empty.
+    pub fn interrupt(&self) {
+        self.queue
+            .lanes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .interrupted = true;
+        self.queue.received.notify_one();
+        self.wake();
+    }
+
+    fn wake(&self) {
+        // Only the first message after the window last processed its queue
+        // needs to wake it.
+        if !self.woken.swap(true, Ordering::AcqRel) {
+            if let Some(waker) = &*self.waker.lock().unwrap_or_else(PoisonError::into_inner) {
+                waker();
+            }
+        }
+    }
+}
+
+impl<User> std::fmt::Debug for WindowSender<User> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowSender")
+            .field("woken", &self.woken)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The receiving half of a window's message queue.
+///
+/// Dropping the receiver discards any queued messages and causes future sends
+/// to fail.
+pub struct WindowReceiver<User>(Arc<MessageQueue<User>>);
+
+impl<User> WindowReceiver<User> {
+    /// Returns the next queued message, preferring the control lane.
+    pub fn try_recv(&self) -> Option<WindowMessage<User>> {
+        let mut lanes = self.0.lanes.lock().unwrap_or_else(PoisonError::into_inner);
+        let lane = if lanes.control.is_empty() {
+            &mut lanes.input
+        } else {
+            &mut lanes.control
+        };
+        let was_full = lane.len() >= LANE_CAPACITY;
+        let message = lane.pop_front()?;
+        lanes.waiting_since = (!lanes.is_empty()).then(Instant::now);
+        drop(lanes);
+        if was_full {
+            self.0.space.notify_all();
+        }
+        Some(message)
+    }
+
+    /// Returns when the window should next be pumped: `wake_at`, or
+    /// immediately if messages are still queued.
+    ///
+    /// Windows draw at most one frame each time they are pumped. Messages
+    /// queued behind a redraw do not wake the window again, so the window
+    /// must be pumped again without waiting for another message.
+    pub fn next_wake(&self, wake_at: Option<Instant>) -> Option<Instant> {
+        let lanes = self.0.lanes.lock().unwrap_or_else(PoisonError::into_inner);
+        if lanes.is_empty() {
+            wake_at
+        } else {
+            Some(Instant::now())
+        }
+    }
+
+    /// Returns true if the window has been forcibly closed.
+    pub fn is_disconnected(&self) -> bool {
+        self.0
+            .lanes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .disconnected
+    }
+
+    /// Blocks until a message is queued, the window is interrupted, or
+    /// `deadline` has passed.
+    pub fn wait(&self, deadline: Option<Instant>) {
+        let mut lanes = self.0.lanes.lock().unwrap_or_else(PoisonError::into_inner);
+        while lanes.is_empty() && !lanes.disconnected && !lanes.interrupted {
+            match deadline {
+                Some(deadline) => {
+                    let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                        break;
+                    };
+                    lanes = self
+                        .0
+                        .received
+                        .wait_timeout(lanes, remaining)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                }
+                None => {
+                    lanes = self
+                        .0
+                        .received
+                        .wait(lanes)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        }
+        lanes.interrupted = false;
+    }
+}
+
+impl<User> Drop for WindowReceiver<User> {
+    fn drop(&mut self) {
+        let mut lanes = self.0.lanes.lock().unwrap_or_else(PoisonError::into_inner);
+        // Queued messages may hold guards the event loop is waiting on, so
+        // they must be dropped rather than kept alive by remaining senders.
+        let discarded = lanes.disconnect();
+        drop(lanes);
+        self.0.space.notify_all();
+        drop(discarded);
+    }
+}
+
+/// A window's behavior that is driven by the thread it runs on.
+pub trait DriveWindow<AppMessage>
+where
+    AppMessage: Message,
+{
+    fn id(&self) -> WindowId;
+
+    /// Processes the window's pending messages and redraws the window if
+    /// needed, without blocking.
+    fn pump(&mut self) -> Pump<AppMessage>;
+
+    /// Returns true if the window has been sent a message since it was last
+    /// pumped.
+    fn woken(&self) -> bool;
+
+    /// Delivers a message directly to the window, bypassing its channel. The
+    /// message is handled the next time the window is pumped.
+    fn deliver(&mut self, message: WindowMessage<AppMessage::Window>);
+
+    /// Restarts the window's behavior after `delay`.
+    fn restart_after(&mut self, delay: Duration);
+
+    /// Waits, without blocking, for the event loop to decide whether the
+    /// window's behavior is restarted after panicking.
+    ///
+    /// The event loop sends the restart delay through `decision`, or drops it
+    /// if the window was closed instead, and then interrupts the window.
+    fn await_restart(&mut self, decision: mpsc::Receiver<Duration>);
+
+    fn restarts(&self) -> u32;
+
+    fn restartable(&self) -> bool;
+}
+
+/// The result of pumping a window.
+#[allow(clippy::large_enum_variant)]
+pub enum Pump<AppMessage>
+where
+    AppMessage: Message,
+{
+    /// The window should be pumped again once a message arrives or the
+    /// deadline, if any, elapses.
+    Wait(Option<Instant>),
+    /// The window's behavior has exited. The message, if any, should be
+    /// handled by the event loop once the driver has been dropped.
+    Exited(Option<EventLoopMessage<AppMessage>>),
+    /// The window's behavior panicked.
+    Panicked(Box<dyn Any + Send>),
+}
+
+/// The result of pumping a window that isn't running on the event loop thread.
+#[allow(clippy::large_enum_variant)]
+pub enum Pumped<AppMessage>
+where
+    AppMessage: Message,
+{
+    Wait(Option<Instant>),
+    /// The window has exited. The message, if any, should be sent to the
+    /// event loop once the driver has been dropped.
+    Exited(Option<EventLoopMessage<AppMessage>>),
+}
+
+/// Returns true if a window that asked to be woken at `wake_at` should be
+/// pumped at `now`.
+pub fn pump_due<AppMessage>(
+    driver: &dyn DriveWindow<AppMessage>,
+    wake_at: Option<Instant>,
+    now: Instant,
+) -> bool
+where
+    AppMessage: Message,
+{
+    driver.woken() || wake_at.is_some_and(|at| at <= now)
+}
+
+/// Pumps a window that isn't running on the event loop thread.
+///
+/// Panics are reported to the event loop using `send_event`, which returns
+/// false if the event loop has exited. The event loop decides whether the
+/// window is restarted.
+pub fn pump_threaded<AppMessage>(
+    driver: &mut dyn DriveWindow<AppMessage>,
+    send_event: impl FnOnce(EventLoopMessage<AppMessage>) -> bool,
+) -> Pumped<AppMessage>
+where
+    AppMessage: Message,
+{
+    match driver.pump() {
+        Pump::Wait(wake_at) => Pumped::Wait(wake_at),
+        Pump::Exited(message) => Pumped::Exited(message),
+        Pump::Panicked(payload) => {
+            // The event loop decides how to handle the panic. If the window
+            // should be restarted, a delay is sent back. Otherwise, the event
+            // loop closes the window. The decision is awaited without blocking
+            // so that a worker can keep driving its other windows.
+            let (restart_sender, restart_receiver) = mpsc::sync_channel(1);
+            if !send_event(EventLoopMessage::WindowPanic {
+                window_id: driver.id(),
+                payload,
+                restarts: driver.restarts(),
+                restartable: driver.restartable(),
+                restart_sender,
+            }) {
+                return Pumped::Exited(None);
+            }
+            driver.await_restart(restart_receiver);
+            Pumped::Wait(None)
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct OpenedWindow(pub(crate) Arc<Mutex<Option<Arc<winit::window::Window>>>>);
 
 impl OpenedWindow {
+    /// Returns a handle for a window that has not been opened yet.
+    pub fn pending() -> Self {
+        Self(Arc::new(Mutex::new(None)))
+    }
+
+    pub(crate) fn set(&self, winit: Arc<winit::window::Window>) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(winit);
+    }
+
     pub fn winit(&self) -> Option<Arc<winit::window::Window>> {
         self.0
             .lock()
@@ -44,25 +589,50 @@ impl OpenedWindow {
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum EventLoopMessage<AppMessage>
 where
     AppMessage: Message,
 {
     OpenWindow {
         attrs: WindowAttributes,
-        sender: Arc<mpsc::SyncSender<WindowMessage<AppMessage::Window>>>,
+        sender: Arc<WindowSender<AppMessage::Window>>,
         open_sender: mpsc::SyncSender<Result<OpenedWindow, OsError>>,
-        spawner: WindowSpawner,
+        spawner: WindowSpawner<AppMessage>,
+    },
+    WakeWindow(WindowId),
+    CloseWindow {
+        window_id: WindowId,
+        reason: CloseReason,
+    },
+    WindowPanic {
+        window_id: WindowId,
+        payload: Box<dyn Any + Send>,
+        restarts: u32,
+        restartable: bool,
+        restart_sender: mpsc::SyncSender<Duration>,
     },
-    CloseWindow(WindowId),
-    WindowPanic(WindowId),
     User {
         message: AppMessage,
         response_sender: mpsc::SyncSender<AppMessage::Response>,
     },
     PreventShutdown,
     AllowShutdown,
-    Error(AppMessage::Error),
+    Error {
+        error: AppMessage::Error,
+        window: Option<WindowId>,
+    },
+    WindowInitError {
+        window_id: WindowId,
+        error: AppMessage::Error,
+    },
+    Quit,
+    WindowResponding(WindowId),
+    QuitResponse {
+        window_id: WindowId,
+        request: u64,
+        allowed: bool,
+    },
     #[cfg(all(target_os = "linux", feature = "xdg"))]
     ThemeChanged(Theme),
 }
@@ -70,7 +640,35 @@ where
 #[derive(Debug)]
 pub enum WindowMessage<User> {
     User(User),
-    Event(WindowEvent),
+    /// An event and the time it was received by the event loop.
+    Event(WindowEvent, Instant),
+    QuitRequested(u64),
+    QuitCancelled,
+    Quit,
+}
+
+impl<User> WindowMessage<User> {
+    /// Returns true if this message is queued in a window's control lane.
+    ///
+    /// Focus changes and file drops are queued with input to preserve their
+    /// ordering relative to the input events around them.
+    fn is_control(&self) -> bool {
+        match self {
+            Self::QuitRequested(_) | Self::QuitCancelled | Self::Quit => true,
+            Self::User(_) => false,
+            Self::Event(event, _) => matches!(
+                event,
+                WindowEvent::RedrawRequested(_)
+                    | WindowEvent::Resized(..)
+                    | WindowEvent::Moved(_)
+                    | WindowEvent::CloseRequested
+                    | WindowEvent::Destroyed
+                    | WindowEvent::ScaleFactorChanged { .. }
+                    | WindowEvent::ThemeChanged(_)
+                    | WindowEvent::Occluded(_)
+            ),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -101,8 +699,10 @@ impl RedrawGuard {
 pub enum WindowEvent {
     RedrawRequested(RedrawGuard),
 
-    /// The size of the window has changed. Contains the client area's new dimensions.
-    Resized(PhysicalSize<u32>),
+    /// The size of the window has changed. Contains the client area's new
+    /// dimensions, and a guard if the event loop is waiting for a frame to be
+    /// drawn at the new size.
+    Resized(PhysicalSize<u32>, Option<RedrawGuard>),
 
     /// The position of the window has changed. Contains the window's new position.
     ///
@@ -241,13 +841,16 @@ pub enum WindowEvent {
     /// * Changing the display's scale factor (e.g. in Control Panel on Windows).
     /// * Moving the window to a display with a different scale factor.
     ///
-    /// After this event callback has been processed, the window will be resized to whatever value
-    /// is pointed to by the `new_inner_size` reference. By default, this will contain the size suggested
-    /// by the OS, but it can be changed to any value.
+    /// After this event has been handled, the window will be resized to the
+    /// size written to `inner_size_writer`. By default, this is the size
+    /// suggested by the OS. The event loop waits a bounded amount of time for
+    /// `guard` to be dropped, allowing the size to be changed.
     ///
     /// For more information about DPI in general, see the [`dpi`](crate::dpi) module.
     ScaleFactorChanged {
         scale_factor: f64,
+        inner_size_writer: InnerSizeWriter,
+        guard: RedrawGuard,
     },
 
     /// The system window theme has changed.
@@ -297,130 +900,477 @@ pub enum WindowEvent {
 }
 
 impl WindowEvent {
+    /// Returns true if this event is user input.
+    pub const fn is_input(&self) -> bool {
+        matches!(
+            self,
+            Self::ReceivedCharacter(_)
+                | Self::KeyboardInput { .. }
+                | Self::ModifiersChanged(_)
+                | Self::Ime(_)
+                | Self::CursorMoved { .. }
+                | Self::MouseWheel { .. }
+                | Self::MouseInput { .. }
+                | Self::TouchpadPressure { .. }
+                | Self::AxisMotion { .. }
+                | Self::Touch(_)
+                | Self::PinchGesture { .. }
+                | Self::PanGesture { .. }
+                | Self::DoubleTapGesture { .. }
+                | Self::RotationGesture { .. }
+        )
+    }
+
     #[allow(clippy::too_many_lines)] // it's a match statement
-    pub fn from_winit(event: winit::event::WindowEvent) -> (Self, Option<WaitForRedraw>) {
+    /// Converts a winit event. When `sync_resize` is true, resize events carry
+    /// a guard that is released once a frame has been drawn at the new size.
+    pub fn from_winit(
+        event: winit::event::WindowEvent,
+        sync_resize: bool,
+    ) -> (Self, Option<WaitForRedraw>) {
+        let resize_guard = || {
+            if sync_resize {
+                let (guard, wait) = RedrawGuard::new();
+                (Some(guard), Some(wait))
+            } else {
+                (None, None)
+            }
+        };
         (
             match event {
-            winit::event::WindowEvent::RedrawRequested => {
-                let (guard, wait) = RedrawGuard::new();
-                return (Self::RedrawRequested(guard), Some(wait))
-            },
-            winit::event::WindowEvent::Resized(size) => Self::Resized(size),
-            winit::event::WindowEvent::Moved(pos) => Self::Moved(pos),
-            winit::event::WindowEvent::CloseRequested => Self::CloseRequested,
-            winit::event::WindowEvent::Destroyed => Self::Destroyed,
-            winit::event::WindowEvent::DroppedFile(path) => Self::DroppedFile(path),
-            winit::event::WindowEvent::HoveredFile(path) => Self::HoveredFile(path),
-            winit::event::WindowEvent::HoveredFileCancelled => Self::HoveredFileCancelled,
-            winit::event::WindowEvent::Focused(focused) => Self::Focused(focused),
-            winit::event::WindowEvent::KeyboardInput {
-                device_id,
-                event,
-                is_synthetic,
-            } => Self::KeyboardInput {
-                device_id,
-                event,
-                is_synthetic,
-            },
+                winit::event::WindowEvent::RedrawRequested => {
+                    let (guard, wait) = RedrawGuard::new();
+                    return (Self::RedrawRequested(guard), Some(wait));
+                }
+                winit::event::WindowEvent::Resized(size) => {
+                    let (guard, wait) = resize_guard();
+                    return (Self::Resized(size, guard), wait);
+                }
+                winit::event::WindowEvent::Moved(pos) => Self::Moved(pos),
+                winit::event::WindowEvent::CloseRequested => Self::CloseRequested,
+                winit::event::WindowEvent::Destroyed => Self::Destroyed,
+                winit::event::WindowEvent::DroppedFile(path) => Self::DroppedFile(path),
+                winit::event::WindowEvent::HoveredFile(path) => Self::HoveredFile(path),
+                winit::event::WindowEvent::HoveredFileCancelled => Self::HoveredFileCancelled,
+                winit::event::WindowEvent::Focused(focused) => Self::Focused(focused),
+                winit::event::WindowEvent::KeyboardInput {
+                    device_id,
+                    event,
+                    is_synthetic,
+                } => Self::KeyboardInput {
+                    device_id,
+                    event,
+                    is_synthetic,
+                },
 
-            winit::event::WindowEvent::ModifiersChanged(modifiers) => {
-                Self::ModifiersChanged(modifiers)
-            }
-            winit::event::WindowEvent::Ime(ime) => Self::Ime(ime),
-            winit::event::WindowEvent::CursorMoved {
-                device_id,
-                position,
-                ..
-            } => Self::CursorMoved {
-                device_id,
-                position,
-            },
-            winit::event::WindowEvent::CursorEntered { device_id } => {
-                Self::CursorEntered { device_id }
-            }
-            winit::event::WindowEvent::CursorLeft { device_id } => Self::CursorLeft { device_id },
-            winit::event::WindowEvent::MouseWheel {
-                device_id,
-                delta,
-                phase,
-                ..
-            } => Self::MouseWheel {
-                device_id,
-                delta,
-                phase,
-            },
-            winit::event::WindowEvent::MouseInput {
-                device_id,
-                state,
-                button,
-                ..
-            } => Self::MouseInput {
-                device_id,
-                state,
-                button,
-            },
-            winit::event::WindowEvent::TouchpadPressure {
-                device_id,
-                pressure,
-                stage,
-            } => Self::TouchpadPressure {
-                device_id,
-                pressure,
-                stage,
-            },
-            winit::event::WindowEvent::AxisMotion {
-                device_id,
-                axis,
-                value,
-            } => Self::AxisMotion {
-                device_id,
-                axis,
-                value,
-            },
-            winit::event::WindowEvent::Touch(touch) => Self::Touch(touch),
-            winit::event::WindowEvent::ScaleFactorChanged {
-                scale_factor,
-                 .. // TODO use the suggested size from the writer <https://github.com/rust-windowing/winit/issues/3080>
-            } => {
-                Self::ScaleFactorChanged {
+                winit::event::WindowEvent::ModifiersChanged(modifiers) => {
+                    Self::ModifiersChanged(modifiers)
+                }
+                winit::event::WindowEvent::Ime(ime) => Self::Ime(ime),
+                winit::event::WindowEvent::CursorMoved {
+                    device_id,
+                    position,
+                    ..
+                } => Self::CursorMoved {
+                    device_id,
+                    position,
+                },
+                winit::event::WindowEvent::CursorEntered { device_id } => {
+                    Self::CursorEntered { device_id }
+                }
+                winit::event::WindowEvent::CursorLeft { device_id } => {
+                    Self::CursorLeft { device_id }
+                }
+                winit::event::WindowEvent::MouseWheel {
+                    device_id,
+                    delta,
+                    phase,
+                    ..
+                } => Self::MouseWheel {
+                    device_id,
+                    delta,
+                    phase,
+                },
+                winit::event::WindowEvent::MouseInput {
+                    device_id,
+                    state,
+                    button,
+                    ..
+                } => Self::MouseInput {
+                    device_id,
+                    state,
+                    button,
+                },
+                winit::event::WindowEvent::TouchpadPressure {
+                    device_id,
+                    pressure,
+                    stage,
+                } => Self::TouchpadPressure {
+                    device_id,
+                    pressure,
+                    stage,
+                },
+                winit::event::WindowEvent::AxisMotion {
+                    device_id,
+                    axis,
+                    value,
+                } => Self::AxisMotion {
+                    device_id,
+                    axis,
+                    value,
+                },
+                winit::event::WindowEvent::Touch(touch) => Self::Touch(touch),
+                winit::event::WindowEvent::ScaleFactorChanged {
                     scale_factor,
+                    inner_size_writer,
+                } => {
+                    let (guard, wait) = RedrawGuard::new();
+                    return (
+                        Self::ScaleFactorChanged {
+                            scale_factor,
+                            inner_size_writer,
+                            guard,
+                        },
+                        Some(wait),
+                    );
                 }
-            },
-            winit::event::WindowEvent::ThemeChanged(theme) => Self::ThemeChanged(theme),
-            winit::event::WindowEvent::Occluded(occluded) => Self::Occluded(occluded),
-            winit::event::WindowEvent::PinchGesture {
-                device_id,
-                delta,
-                phase,
-            } => Self::PinchGesture {
-                device_id,
-                delta,
-                phase,
-            },
-            winit::event::WindowEvent::PanGesture { device_id, delta, phase } => {
-                Self::PanGesture {
+                winit::event::WindowEvent::ThemeChanged(theme) => Self::ThemeChanged(theme),
+                winit::event::WindowEvent::Occluded(occluded) => Self::Occluded(occluded),
+                winit::event::WindowEvent::PinchGesture {
+                    device_id,
+                    delta,
+                    phase,
+                } => Self::PinchGesture {
+                    device_id,
+                    delta,
+                    phase,
+                },
+                winit::event::WindowEvent::PanGesture {
                     device_id,
                     delta,
                     phase,
+                } => Self::PanGesture {
+                    device_id,
+                    delta,
+                    phase,
+                },
+                winit::event::WindowEvent::DoubleTapGesture { device_id } => {
+                    Self::DoubleTapGesture { device_id }
+                }
+                winit::event::WindowEvent::RotationGesture {
+                    device_id,
+                    delta,
+                    phase,
+                } => Self::RotationGesture {
+                    device_id,
+                    delta,
+                    phase,
+                },
+                winit::event::WindowEvent::ActivationTokenDone { serial, token } => {
+                    Self::ActivationTokenDone { serial, token }
                 }
-            }
-            winit::event::WindowEvent::DoubleTapGesture { device_id } => {
-                Self::DoubleTapGesture { device_id }
-            }
-            winit::event::WindowEvent::RotationGesture {
-                device_id,
-                delta,
-                phase,
-            } => Self::RotationGesture {
-                device_id,
-                delta,
-                phase,
             },
-            winit::event::WindowEvent::ActivationTokenDone { serial, token } => {
-                Self::ActivationTokenDone { serial, token }
-            }
-        },
             None,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use winit::window::WindowId;
+
+    use super::{
+        pump_due, pump_threaded, DriveWindow, EventLoopMessage, Pump, Pumped, RedrawGuard,
+        WindowEvent, WindowMessage, WindowReceiver, WindowSender, LANE_CAPACITY,
+    };
+    use crate::Message;
+
+    enum TestApp {}
+
+    impl Message for TestApp {
+        type Window = u32;
+        type Response = ();
+        type Error = ();
+    }
+
+    /// A minimal window driver that, like `RunningWindow`, handles queued
+    /// messages until a frame is drawn each time it is pumped.
+    struct TestWindow {
+        sender: WindowSender<u32>,
+        receiver: WindowReceiver<u32>,
+        frames: usize,
+        handled: Vec<u32>,
+        panic: bool,
+        restart: Option<mpsc::Receiver<Duration>>,
+    }
+
+    impl TestWindow {
+        fn new() -> Self {
+            let (sender, receiver) = WindowSender::queue();
+            Self {
+                sender,
+                receiver,
+                frames: 0,
+                handled: Vec::new(),
+                panic: false,
+                restart: None,
+            }
+        }
+
+        /// Pumps the window the way a pool worker does, until it is neither
+        /// woken nor due.
+        fn drive(&mut self) {
+            let mut wake_at = Some(Instant::now());
+            while pump_due(self, wake_at, Instant::now()) {
+                match pump_threaded(self, |_| -> bool { unreachable!("nothing panics") }) {
+                    Pumped::Wait(next) => wake_at = next,
+                    Pumped::Exited(_) => unreachable!("never exits"),
+                }
+            }
+        }
+    }
+
+    impl DriveWindow<TestApp> for TestWindow {
+        fn id(&self) -> WindowId {
+            WindowId::dummy()
+        }
+
+        fn pump(&mut self) -> Pump<TestApp> {
+            self.sender.clear_woken();
+            if std::mem::take(&mut self.panic) {
+                return Pump::Panicked(Box::new("panicked"));
+            }
+            while let Some(message) = self.receiver.try_recv() {
+                match message {
+                    WindowMessage::Event(WindowEvent::RedrawRequested(guard), _) => {
+                        self.frames += 1;
+                        drop(guard);
+                        break;
+                    }
+                    WindowMessage::User(message) => self.handled.push(message),
+                    _ => unreachable!("not sent"),
+                }
+            }
+            Pump::Wait(self.receiver.next_wake(None))
+        }
+
+        fn woken(&self) -> bool {
+            self.sender.woken()
+        }
+
+        fn deliver(&mut self, message: WindowMessage<u32>) {
+            self.sender.deliver(message);
+        }
+
+        fn restart_after(&mut self, _delay: Duration) {
+            unreachable!("only used by the event loop");
+        }
+
+        fn await_restart(&mut self, decision: mpsc::Receiver<Duration>) {
+            self.restart = Some(decision);
+        }
+
+        fn restarts(&self) -> u32 {
+            0
+        }
+
+        fn restartable(&self) -> bool {
+            true
+        }
+    }
+
+    fn redraw() -> WindowMessage<u32> {
+        let (guard, _wait) = RedrawGuard::new();
+        WindowMessage::Event(WindowEvent::RedrawRequested(guard), Instant::now())
+    }
+
+    #[test]
+    fn messages_queued_behind_a_redraw_are_handled() {
+        let mut window = TestWindow::new();
+        window.sender.try_send(redraw()).unwrap();
+        window.sender.try_send(redraw()).unwrap();
+        window.sender.try_send(WindowMessage::User(1)).unwrap();
+
+        window.drive();
+
+        assert_eq!(window.frames, 2);
+        assert_eq!(window.handled, [1]);
+        assert_eq!(window.sender.waiting_since(), None);
+    }
+
+    #[test]
+    fn panicked_windows_await_a_restart_without_blocking() {
+        let (pumped_sender, pumped) = mpsc::channel();
+        thread::spawn(move || {
+            let mut window = TestWindow::new();
+            window.panic = true;
+            let (events, received) = mpsc::channel();
+            let result = pump_threaded(&mut window, |event| events.send(event).is_ok());
+            pumped_sender
+                .send((matches!(result, Pumped::Wait(None)), window, received))
+                .unwrap();
+        });
+        let (waiting, window, events) = pumped
+            .recv_timeout(Duration::from_secs(5))
+            .expect("pumping blocked on the restart decision");
+        assert!(waiting);
+
+        let Ok(EventLoopMessage::WindowPanic { restart_sender, .. }) = events.try_recv() else {
+            unreachable!("panic reported");
+        };
+        let decision = window.restart.expect("awaiting a restart");
+        assert!(decision.try_recv().is_err());
+        restart_sender.send(Duration::ZERO).unwrap();
+        assert_eq!(decision.try_recv(), Ok(Duration::ZERO));
+    }
+
+    #[test]
+    fn interrupting_wakes_a_waiting_window() {
+        let (sender, receiver) = WindowSender::<u32>::queue();
+        let (woke, woken) = mpsc::channel();
+        thread::spawn(move || {
+            receiver.wait(None);
+            woke.send(()).unwrap();
+        });
+        sender.interrupt();
+        woken
+            .recv_timeout(Duration::from_secs(5))
+            .expect("window was not woken");
+        assert!(sender.woken());
+    }
+
+    #[test]
+    fn waiting_since_tracks_progress() {
+        let (sender, receiver) = WindowSender::<u32>::queue();
+        assert_eq!(sender.waiting_since(), None);
+
+        sender.try_send(WindowMessage::User(1)).unwrap();
+        sender.try_send(WindowMessage::User(2)).unwrap();
+        let queued = sender.waiting_since().expect("messages queued");
+
+        // Receiving a message is progress, even if more messages are waiting.
+        assert!(receiver.try_recv().is_some());
+        let progressed = sender.waiting_since().expect("message still queued");
+        assert!(progressed >= queued);
+
+        assert!(receiver.try_recv().is_some());
+        assert_eq!(sender.waiting_since(), None);
+    }
+
+    #[test]
+    fn restarting_windows_are_not_waiting() {
+        let (sender, _receiver) = WindowSender::<u32>::queue();
+        sender.set_restarting(true);
+        sender.try_send(WindowMessage::User(1)).unwrap();
+        assert_eq!(sender.waiting_since(), None);
+
+        let restarted = Instant::now();
+        sender.set_restarting(false);
+        let waiting_since = sender.waiting_since().expect("message queued");
+        assert!(waiting_since >= restarted);
+    }
+
+    #[test]
+    fn responding_is_reported_once() {
+        let (sender, _receiver) = WindowSender::<u32>::queue();
+        assert!(!sender.set_responding());
+        assert!(sender.set_unresponsive());
+        assert!(!sender.set_unresponsive());
+        assert!(!sender.responding());
+        assert!(sender.set_responding());
+        assert!(!sender.set_responding());
+        assert!(sender.responding());
+    }
+
+    fn user(message: &WindowMessage<u32>) -> Option<u32> {
+        match message {
+            WindowMessage::User(message) => Some(*message),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn control_messages_overtake_input() {
+        let (sender, receiver) = WindowSender::<u32>::queue();
+        sender.try_send(WindowMessage::User(1)).unwrap();
+        sender.try_send(WindowMessage::User(2)).unwrap();
+        sender.try_send(WindowMessage::Quit).unwrap();
+        sender.try_send(WindowMessage::User(3)).unwrap();
+
+        assert!(matches!(receiver.try_recv(), Some(WindowMessage::Quit)));
+        let input = std::iter::from_fn(|| receiver.try_recv())
+            .map(|message| user(&message).expect("input"))
+            .collect::<Vec<_>>();
+        assert_eq!(input, [1, 2, 3]);
+    }
+
+    #[test]
+    fn full_lanes_wake_blocked_senders_when_drained() {
+        let (sender, receiver) = WindowSender::<u32>::queue();
+        let sender = Arc::new(sender);
+        for message in 0..LANE_CAPACITY {
+            sender
+                .try_send(WindowMessage::User(u32::try_from(message).unwrap()))
+                .unwrap();
+        }
+        assert!(matches!(
+            sender.try_send(WindowMessage::User(0)),
+            Err(mpsc::TrySendError::Full(_))
+        ));
+        // Control messages have their own lane.
+        sender.try_send(WindowMessage::Quit).unwrap();
+
+        let blocked = thread::spawn({
+            let sender = sender.clone();
+            move || sender.send(WindowMessage::User(u32::MAX)).is_ok()
+        });
+        thread::sleep(Duration::from_millis(50));
+        assert!(!blocked.is_finished());
+
+        // Receiving the control message does not free input space.
+        assert!(matches!(receiver.try_recv(), Some(WindowMessage::Quit)));
+        assert!(receiver.try_recv().is_some());
+        assert!(blocked.join().unwrap());
+    }
+
+    #[test]
+    fn disconnecting_discards_queued_messages() {
+        let (sender, receiver) = WindowSender::<u32>::queue();
+        let (guard, wait) = RedrawGuard::new();
+        sender
+            .try_send(WindowMessage::Event(
+                WindowEvent::RedrawRequested(guard),
+                Instant::now(),
+            ))
+            .unwrap();
+        sender.try_send(WindowMessage::User(1)).unwrap();
+        assert!(wait.0.try_recv().is_err());
+
+        drop(receiver);
+        // The guard was dropped, releasing anything waiting on the redraw.
+        assert!(wait.0.try_recv().is_ok());
+        assert_eq!(sender.waiting_since(), None);
+        assert!(matches!(
+            sender.try_send(WindowMessage::User(2)),
+            Err(mpsc::TrySendError::Disconnected(_))
+        ));
+        assert!(sender.send(WindowMessage::User(3)).is_err());
+    }
+
+    #[test]
+    fn force_closing_discards_queued_messages() {
+        let (sender, receiver) = WindowSender::<u32>::queue();
+        sender.try_send(WindowMessage::User(1)).unwrap();
+        sender.force_close();
+        assert!(receiver.is_disconnected());
+        assert!(receiver.try_recv().is_none());
+        assert!(matches!(
+            sender.send_timeout(WindowMessage::User(2), Duration::from_secs(1)),
+            Err(mpsc::TrySendError::Disconnected(_))
+        ));
+    }
+}
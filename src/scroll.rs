@@ -0,0 +1,340 @@
+use std::time::{Duration, Instant};
+
+use winit::dpi::PhysicalPosition;
+use winit::event::{DeviceId, MouseScrollDelta, TouchPhase};
+
+/// Settings for converting scroll events into pixel deltas.
+///
+/// Scroll processing is enabled using
+/// [`RunningWindow::set_scroll_processing`](crate::RunningWindow::set_scroll_processing).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollSettings {
+    /// The number of logical pixels scrolled per line when a device reports
+    /// scrolling in lines. Defaults to 20.
+    pub line_height: f64,
+    /// When true, scrolling continues with decaying velocity after a
+    /// touchpad scroll gesture ends. Defaults to true.
+    pub momentum: bool,
+    /// The time it takes for momentum scrolling to lose roughly two thirds of
+    /// its velocity. Defaults to 325 milliseconds.
+    pub momentum_decay: Duration,
+}
+
+impl Default for ScrollSettings {
+    fn default() -> Self {
+        Self {
+            line_height: 20.,
+            momentum: true,
+            momentum_decay: Duration::from_millis(325),
+        }
+    }
+}
+
+/// The phase of a processed scroll event.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ScrollPhase {
+    /// A scroll gesture has started.
+    Started,
+    /// A scroll gesture is in progress, or a device without gestures, such as
+    /// a mouse wheel, has scrolled.
+    Moved,
+    /// A scroll gesture has ended. If momentum scrolling is enabled and the
+    /// gesture was moving fast enough, momentum scrolling begins.
+    Ended,
+    /// A scroll gesture was cancelled.
+    Cancelled,
+    /// Scrolling is continuing due to the momentum of a gesture that has
+    /// ended.
+    Momentum,
+    /// Momentum scrolling has finished, either due to the velocity decaying
+    /// or a new scroll event being received.
+    MomentumEnded,
+}
+
+/// Velocities slower than this, in pixels per second, do not produce
+/// momentum.
+const MIN_VELOCITY: f64 = 20.;
+/// If a gesture paused for longer than this before ending, it has no
+/// momentum.
+const MAX_RELEASE_DELAY: Duration = Duration::from_millis(50);
+
+pub(crate) struct ScrollProcessor {
+    settings: ScrollSettings,
+    velocity: PhysicalPosition<f64>,
+    last_event: Option<Instant>,
+    momentum: Option<Momentum>,
+}
+
+/// The result of processing a scroll event.
+pub(crate) struct Scrolled {
+    /// The device whose momentum scrolling was interrupted by this event.
+    pub interrupted: Option<DeviceId>,
+    pub delta: PhysicalPosition<f64>,
+    pub phase: ScrollPhase,
+}
+
+struct Momentum {
+    device_id: DeviceId,
+    velocity: PhysicalPosition<f64>,
+    last_step: Instant,
+}
+
+impl ScrollProcessor {
+    pub fn new(settings: ScrollSettings) -> Self {
+        Self {
+            settings,
+            velocity: PhysicalPosition::default(),
+            last_event: None,
+            momentum: None,
+        }
+    }
+
+    pub const fn settings(&self) -> &ScrollSettings {
+        &self.settings
+    }
+
+    pub const fn momentum_active(&self) -> bool {
+        self.momentum.is_some()
+    }
+
+    /// Converts a scroll event into a pixel delta. Any momentum scrolling is
+    /// interrupted by the new event.
+    pub fn scroll(
+        &mut self,
+        device_id: DeviceId,
+        delta: MouseScrollDelta,
+        phase: TouchPhase,
+        scale: f64,
+        now: Instant,
+    ) -> Scrolled {
+        let interrupted = self.momentum.take().map(|momentum| momentum.device_id);
+        let delta = match delta {
+            MouseScrollDelta::LineDelta(x, y) => {
+                let line_height = self.settings.line_height * scale;
+                PhysicalPosition::new(f64::from(x) * line_height, f64::from(y) * line_height)
+            }
+            MouseScrollDelta::PixelDelta(delta) => delta,
+        };
+
+        let phase = match phase {
+            TouchPhase::Started => {
+                self.velocity = PhysicalPosition::default();
+                self.last_event = Some(now);
+                ScrollPhase::Started
+            }
+            TouchPhase::Moved => {
+                self.track_velocity(delta, now);
+                ScrollPhase::Moved
+            }
+            TouchPhase::Ended => {
+                // The delay must be measured before tracking the final event
+                // updates the time of the last event.
+                let last_event = self.last_event;
+                self.track_velocity(delta, now);
+                self.begin_momentum(device_id, last_event, now);
+                ScrollPhase::Ended
+            }
+            TouchPhase::Cancelled => {
+                self.last_event = None;
+                ScrollPhase::Cancelled
+            }
+        };
+        Scrolled {
+            interrupted,
+            delta,
+            phase,
+        }
+    }
+
+    fn track_velocity(&mut self, delta: PhysicalPosition<f64>, now: Instant) {
+        let Some(last_event) = self.last_event.replace(now) else {
+            return;
+        };
+        let elapsed = now.saturating_duration_since(last_event).as_secs_f64();
+        if elapsed <= 0. {
+            return;
+        }
+        // Smooth the velocity, as the interval between events is irregular.
+        self.velocity.x = self.velocity.x * 0.5 + delta.x / elapsed * 0.5;
+        self.velocity.y = self.velocity.y * 0.5 + delta.y / elapsed * 0.5;
+    }
+
+    fn begin_momentum(&mut self, device_id: DeviceId, last_event: Option<Instant>, now: Instant) {
+        self.last_event = None;
+        if !self.settings.momentum
+            || last_event.map_or(true, |last| {
+                now.saturating_duration_since(last) > MAX_RELEASE_DELAY
+            })
+            || speed(self.velocity) < MIN_VELOCITY
+        {
+            return;
+        }
+
+        self.momentum = Some(Momentum {
+            device_id,
+            velocity: self.velocity,
+            last_step: now,
+        });
+    }
+
+    /// Advances momentum scrolling, returning the distance scrolled since the
+    /// last step.
+    pub fn step(&mut self, now: Instant) -> Option<(DeviceId, PhysicalPosition<f64>, ScrollPhase)> {
+        let momentum = self.momentum.as_mut()?;
+        let elapsed = now
+            .saturating_duration_since(momentum.last_step)
+            .as_secs_f64();
+        momentum.last_step = now;
+
+        // The velocity decays exponentially. The distance travelled is the
+        // integral of the velocity over the elapsed time.
+        let time_constant = self.settings.momentum_decay.as_secs_f64().max(f64::EPSILON);
+        let decay = (-elapsed / time_constant).exp();
+        let travelled = time_constant * (1. - decay);
+        let delta = PhysicalPosition::new(
+            momentum.velocity.x * travelled,
+            momentum.velocity.y * travelled,
+        );
+        momentum.velocity.x *= decay;
+        momentum.velocity.y *= decay;
+
+        let device_id = momentum.device_id;
+        if speed(momentum.velocity) < MIN_VELOCITY {
+            self.momentum = None;
+            Some((device_id, delta, ScrollPhase::MomentumEnded))
+        } else {
+            Some((device_id, delta, ScrollPhase::Momentum))
+        }
+    }
+}
+
+fn speed(velocity: PhysicalPosition<f64>) -> f64 {
+    velocity.x.hypot(velocity.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use winit::dpi::PhysicalPosition;
+    use winit::event::{DeviceId, MouseScrollDelta, TouchPhase};
+
+    use super::{ScrollPhase, ScrollProcessor, ScrollSettings, MAX_RELEASE_DELAY};
+
+    fn device() -> DeviceId {
+        DeviceId::dummy()
+    }
+
+    fn pixels(y: f64) -> MouseScrollDelta {
+        MouseScrollDelta::PixelDelta(PhysicalPosition::new(0., y))
+    }
+
+    /// Performs a gesture that scrolls `distance` pixels every 10ms, pausing
+    /// for `release_delay` before ending.
+    fn gesture(scroll: &mut ScrollProcessor, distance: f64, release_delay: Duration) -> Instant {
+        let mut now = Instant::now();
+        scroll.scroll(device(), pixels(0.), TouchPhase::Started, 1., now);
+        for _ in 0..5 {
+            now += Duration::from_millis(10);
+            scroll.scroll(device(), pixels(distance), TouchPhase::Moved, 1., now);
+        }
+        now += release_delay;
+        let ended = scroll.scroll(device(), pixels(0.), TouchPhase::Ended, 1., now);
+        assert_eq!(ended.phase, ScrollPhase::Ended);
+        now
+    }
+
+    #[test]
+    fn line_deltas_are_scaled() {
+        let mut scroll = ScrollProcessor::new(ScrollSettings::default());
+        let scrolled = scroll.scroll(
+            device(),
+            MouseScrollDelta::LineDelta(1., -2.),
+            TouchPhase::Moved,
+            2.,
+            Instant::now(),
+        );
+        assert_eq!(scrolled.delta, PhysicalPosition::new(40., -80.));
+        assert_eq!(scrolled.phase, ScrollPhase::Moved);
+    }
+
+    #[test]
+    fn fast_gestures_have_momentum() {
+        let mut scroll = ScrollProcessor::new(ScrollSettings::default());
+        gesture(&mut scroll, 10., Duration::from_millis(10));
+        assert!(scroll.momentum_active());
+    }
+
+    #[test]
+    fn slow_gestures_have_no_momentum() {
+        let mut scroll = ScrollProcessor::new(ScrollSettings::default());
+        // 0.1 pixels every 10ms is 10 pixels per second, which is slower than
+        // MIN_VELOCITY.
+        gesture(&mut scroll, 0.1, Duration::from_millis(10));
+        assert!(!scroll.momentum_active());
+    }
+
+    #[test]
+    fn paused_gestures_have_no_momentum() {
+        let mut scroll = ScrollProcessor::new(ScrollSettings::default());
+        gesture(
+            &mut scroll,
+            10.,
+            MAX_RELEASE_DELAY + Duration::from_millis(1),
+        );
+        assert!(!scroll.momentum_active());
+    }
+
+    #[test]
+    fn momentum_is_disabled_by_settings() {
+        let mut scroll = ScrollProcessor::new(ScrollSettings {
+            momentum: false,
+            ..ScrollSettings::default()
+        });
+        gesture(&mut scroll, 10., Duration::from_millis(10));
+        assert!(!scroll.momentum_active());
+    }
+
+    #[test]
+    fn momentum_decays_until_ended() {
+        let mut scroll = ScrollProcessor::new(ScrollSettings::default());
+        let mut now = gesture(&mut scroll, 10., Duration::from_millis(10));
+
+        let mut previous = f64::INFINITY;
+        let mut steps = 0;
+        loop {
+            now += Duration::from_millis(16);
+            let (_, delta, phase) = scroll.step(now).expect("momentum active");
+            assert!(delta.y > 0.);
+            assert!(delta.y < previous, "momentum must decay");
+            previous = delta.y;
+            steps += 1;
+            if phase == ScrollPhase::MomentumEnded {
+                break;
+            }
+            assert_eq!(phase, ScrollPhase::Momentum);
+            assert!(steps < 1000, "momentum never ended");
+        }
+        assert!(!scroll.momentum_active());
+        assert!(scroll.step(now).is_none());
+    }
+
+    #[test]
+    fn new_events_interrupt_momentum() {
+        let mut scroll = ScrollProcessor::new(ScrollSettings::default());
+        let now = gesture(&mut scroll, 10., Duration::from_millis(10));
+        assert!(scroll.momentum_active());
+
+        let scrolled = scroll.scroll(
+            device(),
+            pixels(0.),
+            TouchPhase::Started,
+            1.,
+            now + Duration::from_millis(16),
+        );
+        assert_eq!(scrolled.interrupted, Some(device()));
+        assert_eq!(scrolled.phase, ScrollPhase::Started);
+        assert!(!scroll.momentum_active());
+        assert!(scroll.step(now + Duration::from_millis(32)).is_none());
+    }
+}
@@ -1,34 +1,47 @@
-use std::collections::HashSet;
-use std::ops::{Deref, DerefMut};
+use std::any::TypeId;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::ops::{BitOr, BitOrAssign, Deref, DerefMut};
 use std::panic::AssertUnwindSafe;
 use std::path::PathBuf;
-use std::sync::{mpsc, Arc, PoisonError, Weak};
+use std::sync::{mpsc, Arc, Mutex, PoisonError, Weak};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use winit::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
 use winit::error::{EventLoopError, OsError};
 use winit::event::{
-    AxisId, DeviceId, ElementState, Ime, KeyEvent, Modifiers, MouseButton, MouseScrollDelta, Touch,
-    TouchPhase,
+    AxisId, DeviceId, ElementState, Ime, InnerSizeWriter, KeyEvent, Modifiers, MouseButton,
+    MouseScrollDelta, Touch, TouchPhase,
 };
-use winit::keyboard::PhysicalKey;
+use winit::keyboard::{Key, KeyCode, KeyLocation, NamedKey, PhysicalKey};
 use winit::window::{Fullscreen, Icon, Theme, WindowButtons, WindowId, WindowLevel};
 
-use crate::private::{self, OpenedWindow, RedrawGuard, WindowEvent, WindowSpawner};
+use crate::private::{
+    self, DriveWindow, LocalSpawner, OpenedWindow, Pump, Pumped, RedrawGuard, WindowEvent,
+    WindowReceiver, WindowSender, WindowSpawner,
+};
+use crate::scroll::{ScrollPhase, ScrollProcessor, ScrollSettings, Scrolled};
 use crate::{
-    App, Application, AsApplication, EventLoopMessage, ExecutingApp, Message, PendingApp,
-    WindowMessage,
+    App, Application, AsApplication, CloseReason, EventLoopMessage, ExecutingApp, Message,
+    PendingApp, WindowMessage,
 };
 
 /// A weak reference to a running window.
 #[derive(Debug)]
 pub struct Window<Message> {
-    opened: OpenedWindow,
-    sender: Weak<mpsc::SyncSender<WindowMessage<Message>>>,
+    pub(crate) opened: OpenedWindow,
+    sender: Weak<WindowSender<Message>>,
 }
 
 impl<Message> Window<Message> {
+    pub(crate) fn new(opened: OpenedWindow, sender: &Arc<WindowSender<Message>>) -> Self {
+        Self {
+            opened,
+            sender: Arc::downgrade(sender),
+        }
+    }
+
     /// Returns the winit id of the window.
     #[must_use]
     pub fn id(&self) -> Option<WindowId> {
@@ -46,6 +59,10 @@ impl<Message> Window<Message> {
     /// be received even if this function returns `Ok`, if the window closes
     /// between when the message was sent and when the message is received.
     ///
+    /// This function blocks while the window's message queue is full. Use
+    /// [`Window::try_send`] or [`Window::send_timeout`] to avoid blocking on a
+    /// window that is not responding.
+    ///
     /// # Errors
     ///
     /// If the window is already closed, this function returns `Err(message)`.
@@ -59,8 +76,111 @@ impl<Message> Window<Message> {
             _ => unreachable!("same input as output"),
         }
     }
+
+    /// Returns true if the window is open and responding.
+    ///
+    /// A window is not responding once it has had messages waiting to be
+    /// processed for longer than the app's
+    /// [unresponsive threshold](crate::PendingApp::set_unresponsive_threshold).
+    /// The window is considered responding again as soon as it processes
+    /// another message.
+    #[must_use]
+    pub fn is_responding(&self) -> bool {
+        self.sender
+            .upgrade()
+            .is_some_and(|sender| sender.responding())
+    }
+
+    /// Sends a message to the window without blocking.
+    ///
+    /// Unlike [`Window::send`], this function returns immediately if the
+    /// window's message queue is full.
+    ///
+    /// # Errors
+    ///
+    /// - [`TrySendError::Full`]: The window's message queue is full. This
+    ///   usually indicates the window is not keeping up with the messages it
+    ///   is being sent.
+    /// - [`TrySendError::Closed`]: The window is closed.
+    pub fn try_send(&self, message: Message) -> Result<(), TrySendError<Message>> {
+        let Some(sender) = self.sender.upgrade() else {
+            return Err(TrySendError::Closed(message));
+        };
+        sender
+            .try_send(WindowMessage::User(message))
+            .map_err(TrySendError::from_queue)
+    }
+
+    /// Sends a message to the window, waiting up to `timeout` for space in
+    /// the window's message queue.
+    ///
+    /// # Errors
+    ///
+    /// - [`TrySendError::Full`]: The window's message queue remained full
+    ///   until `timeout` elapsed.
+    /// - [`TrySendError::Closed`]: The window is closed.
+    pub fn send_timeout(
+        &self,
+        message: Message,
+        timeout: Duration,
+    ) -> Result<(), TrySendError<Message>> {
+        let Some(sender) = self.sender.upgrade() else {
+            return Err(TrySendError::Closed(message));
+        };
+        sender
+            .send_timeout(WindowMessage::User(message), timeout)
+            .map_err(TrySendError::from_queue)
+    }
+}
+
+/// An error from [`Window::try_send`] or [`Window::send_timeout`].
+///
+/// The message that could not be sent is returned in each variant.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum TrySendError<Message> {
+    /// The window's message queue is full.
+    Full(Message),
+    /// The window is closed.
+    Closed(Message),
+}
+
+impl<Message> TrySendError<Message> {
+    /// Returns the message that could not be sent.
+    pub fn into_inner(self) -> Message {
+        match self {
+            Self::Full(message) | Self::Closed(message) => message,
+        }
+    }
+
+    fn from_queue(error: mpsc::TrySendError<WindowMessage<Message>>) -> Self {
+        match error {
+            mpsc::TrySendError::Full(WindowMessage::User(message)) => Self::Full(message),
+            mpsc::TrySendError::Disconnected(WindowMessage::User(message)) => Self::Closed(message),
+            _ => unreachable!("same input as output"),
+        }
+    }
+}
+
+impl<Message> std::fmt::Debug for TrySendError<Message> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("Full(..)"),
+            Self::Closed(_) => f.write_str("Closed(..)"),
+        }
+    }
 }
 
+impl<Message> std::fmt::Display for TrySendError<Message> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full(_) => f.write_str("the window's message queue is full"),
+            Self::Closed(_) => f.write_str("the window is closed"),
+        }
+    }
+}
+
+impl<Message> std::error::Error for TrySendError<Message> {}
+
 impl<Message> Clone for Window<Message> {
     fn clone(&self) -> Self {
         Self {
@@ -79,10 +199,13 @@ where
 {
     owner: &'a mut Application,
     context: Behavior::Context,
+    restart_context: Option<ContextFactory<Behavior::Context>>,
     attributes: WindowAttributes,
 }
-impl<'a, Behavior, Application, AppMessage> Deref
-    for WindowBuilder<'a, Behavior, Application, AppMessage>
+
+type ContextFactory<Context> = Box<dyn FnMut() -> Context + Send>;
+impl<Behavior, Application, AppMessage> Deref
+    for WindowBuilder<'_, Behavior, Application, AppMessage>
 where
     Behavior: self::WindowBehavior<AppMessage>,
     AppMessage: Message,
@@ -95,8 +218,8 @@ where
     }
 }
 
-impl<'a, Behavior, Application, AppMessage> DerefMut
-    for WindowBuilder<'a, Behavior, Application, AppMessage>
+impl<Behavior, Application, AppMessage> DerefMut
+    for WindowBuilder<'_, Behavior, Application, AppMessage>
 where
     Behavior: self::WindowBehavior<AppMessage>,
     AppMessage: Message,
@@ -162,6 +285,33 @@ pub struct WindowAttributes {
     /// - class name on windows
     #[doc(alias("app_id", "class", "class_name"))]
     pub app_name: Option<String>,
+    /// A tag identifying this window, allowing it to be found using
+    /// [`Windows::find`](crate::Windows::find).
+    pub tag: Option<WindowTag>,
+    /// The action to take when [`WindowBehavior::try_redraw`] or
+    /// [`WindowBehavior::try_resized`] returns an error.
+    pub runtime_error_action: RuntimeErrorAction,
+    /// Determines how long the event loop waits for the window to finish
+    /// redrawing after the operating system requests a redraw.
+    pub redraw_sync: RedrawSync,
+    /// When set, the event loop waits up to this duration after the window is
+    /// resized for the window to draw a frame at its new size.
+    ///
+    /// This prevents the window from being presented with contents drawn at
+    /// its previous size while it is being resized, at the expense of blocking
+    /// the event loop. Defaults to `None`.
+    pub resize_sync_timeout: Option<Duration>,
+    /// The categories of events delivered to the window. Defaults to
+    /// [`EventCategories::ALL`].
+    ///
+    /// Excluding categories the window's behavior ignores avoids the cost of
+    /// sending those events to the window.
+    pub events: EventCategories,
+    /// Determines where the window's behavior runs.
+    pub execution: ExecutionMode,
+    /// Options for the thread the window's behavior runs on when using
+    /// [`ExecutionMode::Dedicated`].
+    pub thread: ThreadAttributes,
 }
 
 impl Default for WindowAttributes {
@@ -189,10 +339,377 @@ impl Default for WindowAttributes {
             active: defaults.active,
             app_name: None,
             delay_visible: true,
+            tag: None,
+            runtime_error_action: RuntimeErrorAction::default(),
+            redraw_sync: RedrawSync::default(),
+            resize_sync_timeout: None,
+            events: EventCategories::default(),
+            execution: ExecutionMode::default(),
+            thread: ThreadAttributes::default(),
+        }
+    }
+}
+
+/// An action taken after a fallible [`WindowBehavior`] callback returns an
+/// error.
+///
+/// Regardless of the action, the error is reported to
+/// [`PendingApp::on_error`](crate::PendingApp::on_error).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum RuntimeErrorAction {
+    /// Continue running the window as if no error occurred.
+    #[default]
+    Ignore,
+    /// Close the window.
+    CloseWindow,
+    /// Redraw the window again after one frame of the monitor's refresh rate.
+    /// If the error was returned from [`WindowBehavior::try_resized`], it is
+    /// invoked again before the next redraw.
+    RetryNextFrame,
+}
+
+/// Determines how the event loop synchronizes with a window when the
+/// operating system requests that the window be redrawn.
+///
+/// While the event loop is waiting for a window to redraw, it is unable to
+/// process events for any other window. Waiting allows the window's contents
+/// to stay in sync with its size as it is being resized on some platforms.
+///
+/// Windows using [`ExecutionMode::EventLoop`] are redrawn before the event
+/// loop continues, and the event loop never waits on them. The event loop
+/// also stops waiting on windows that have been detected as not responding.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum RedrawSync {
+    /// The event loop waits until the window has finished redrawing, for up
+    /// to [`WindowAttributes::resize_sync_timeout`] if it is set, or one second
+    /// otherwise.
+    ///
+    /// The behavior should not wait on the event loop, such as by calling
+    /// [`Application::send`], while redrawing, as the event loop will not
+    /// respond until the wait times out.
+    Synchronous,
+    /// The event loop waits for the window to finish redrawing for up to one
+    /// frame of the window's current monitor's refresh rate. If the refresh
+    /// rate can't be determined, 60hz is assumed.
+    #[default]
+    RefreshRate,
+    /// The event loop continues processing events immediately without
+    /// waiting for the window to redraw.
+    Asynchronous,
+}
+
+impl RedrawSync {
+    pub(crate) fn frame_duration(window: &winit::window::Window) -> Duration {
+        window
+            .current_monitor()
+            .and_then(|monitor| monitor.refresh_rate_millihertz())
+            .filter(|millihertz| *millihertz > 0)
+            .map_or(Duration::from_micros(16_667), |millihertz| {
+                Duration::from_nanos(1_000_000_000_000 / u64::from(millihertz))
+            })
+    }
+}
+
+/// A set of categories of events delivered to a window.
+///
+/// Events that aren't in a window's [`WindowAttributes::events`] are dropped
+/// by the event loop before being sent to the window. Events that update the
+/// window's state, such as resizing, focus, and redraw requests, are always
+/// delivered.
+///
+/// When a category is excluded, the state [`RunningWindow`] tracks from those
+/// events is not updated. For example, excluding [`EventCategories::POINTER`]
+/// prevents [`RunningWindow::cursor_position`] from being updated.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct EventCategories(u8);
+
+impl EventCategories {
+    /// No optional events.
+    pub const NONE: Self = Self(0);
+    /// Keyboard input, modifier changes, and IME events.
+    pub const KEYBOARD: Self = Self(1 << 0);
+    /// Cursor movement, cursor enter and leave, and mouse button events.
+    pub const POINTER: Self = Self(1 << 1);
+    /// Mouse wheel and touchpad scroll events.
+    pub const SCROLL: Self = Self(1 << 2);
+    /// Touch and touchpad pressure events.
+    pub const TOUCH: Self = Self(1 << 3);
+    /// Pinch, pan, double tap, and rotation gestures.
+    pub const GESTURES: Self = Self(1 << 4);
+    /// Motion on device axes, such as joysticks.
+    pub const AXIS_MOTION: Self = Self(1 << 5);
+    /// File hover and drop events.
+    pub const FILES: Self = Self(1 << 6);
+    /// All events. This is the default.
+    pub const ALL: Self = Self(0x7f);
+
+    /// Returns true if every category in `other` is in this set.
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns this set without the categories in `other`.
+    #[must_use]
+    pub const fn without(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// Returns true if `event` should be delivered to a window that requested
+    /// these categories.
+    pub(crate) const fn includes(self, event: &winit::event::WindowEvent) -> bool {
+        use winit::event::WindowEvent;
+        let category = match event {
+            WindowEvent::KeyboardInput { .. }
+            | WindowEvent::ModifiersChanged(_)
+            | WindowEvent::Ime(_) => Self::KEYBOARD,
+            WindowEvent::CursorMoved { .. }
+            | WindowEvent::CursorEntered { .. }
+            | WindowEvent::CursorLeft { .. }
+            | WindowEvent::MouseInput { .. } => Self::POINTER,
+            WindowEvent::MouseWheel { .. } => Self::SCROLL,
+            WindowEvent::Touch(_) | WindowEvent::TouchpadPressure { .. } => Self::TOUCH,
+            WindowEvent::PinchGesture { .. }
+            | WindowEvent::PanGesture { .. }
+            | WindowEvent::DoubleTapGesture { .. }
+            | WindowEvent::RotationGesture { .. } => Self::GESTURES,
+            WindowEvent::AxisMotion { .. } => Self::AXIS_MOTION,
+            WindowEvent::DroppedFile(_)
+            | WindowEvent::HoveredFile(_)
+            | WindowEvent::HoveredFileCancelled => Self::FILES,
+            _ => return true,
+        };
+        self.contains(category)
+    }
+}
+
+impl Default for EventCategories {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl BitOr for EventCategories {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for EventCategories {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Determines where a window's behavior runs.
+///
+/// Regardless of the mode, the same [`WindowBehavior`] callbacks are invoked.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum ExecutionMode {
+    /// The behavior runs on a thread spawned for the window, configured using
+    /// [`WindowAttributes::thread`].
+    #[default]
+    Dedicated,
+    /// The behavior runs on a pool of worker threads shared with other windows.
+    ///
+    /// Each worker takes turns driving the windows assigned to it, so a
+    /// behavior that blocks delays the other windows on its worker. The size of
+    /// the pool can be configured using
+    /// [`PendingApp::set_worker_threads`](crate::PendingApp::set_worker_threads).
+    Shared,
+    /// The behavior runs on the event loop thread.
+    ///
+    /// This is useful for renderers that must be used from the main thread.
+    /// Redraw requests, resizes, and scale factor changes are delivered to the
+    /// behavior as soon as the event loop receives them. Other events are
+    /// batched and delivered once the event loop has no more pending events.
+    /// While the behavior is running, the event loop is unable to process
+    /// other events. Because of this, [`Application::send`] and
+    /// [`WindowBuilder::open`] do not wait for the event loop when called from
+    /// the behavior, and instead return `None`.
+    EventLoop,
+}
+
+/// Options for the thread a window's behavior runs on.
+#[derive(Clone, Default)]
+pub struct ThreadAttributes {
+    /// The name of the thread. The name is visible in profilers and panic
+    /// messages.
+    pub name: Option<String>,
+    /// The size of the thread's stack, in bytes. When `None`, the standard
+    /// library's default stack size is used.
+    pub stack_size: Option<usize>,
+    /// The scheduling niceness to apply to the thread. Higher values give the
+    /// thread a lower scheduling priority.
+    ///
+    /// If the niceness cannot be applied, such as due to insufficient
+    /// privileges, the thread runs with its inherited niceness and the failure
+    /// is ignored.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Linux:** Applied using `setpriority`. Lowering the niceness below
+    ///   zero typically requires elevated privileges.
+    /// - **Other platforms:** Unsupported.
+    pub niceness: Option<i32>,
+    /// A function that is invoked on the thread before the window's behavior is
+    /// initialized.
+    ///
+    /// This can be used to perform per-thread setup, such as registering the
+    /// thread with a tracing subscriber.
+    pub on_start: Option<Arc<dyn Fn() + Send + Sync>>,
+}
+
+impl ThreadAttributes {
+    fn builder(&self) -> thread::Builder {
+        let mut builder = thread::Builder::new();
+        if let Some(name) = &self.name {
+            builder = builder.name(name.clone());
+        }
+        if let Some(stack_size) = self.stack_size {
+            builder = builder.stack_size(stack_size);
+        }
+        builder
+    }
+
+    fn setup(&self) {
+        if let Some(niceness) = self.niceness {
+            // Niceness is only a scheduling hint, so failing to apply it is
+            // not an error.
+            let _result = set_thread_niceness(niceness);
+        }
+        if let Some(on_start) = &self.on_start {
+            on_start();
+        }
+    }
+}
+
+impl std::fmt::Debug for ThreadAttributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThreadAttributes")
+            .field("name", &self.name)
+            .field("stack_size", &self.stack_size)
+            .field("niceness", &self.niceness)
+            .field("on_start", &self.on_start.as_ref().map(|_| "Fn()"))
+            .finish()
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+fn set_thread_niceness(niceness: i32) -> std::io::Result<()> {
+    // On Linux, each thread has its own niceness, and a `who` of 0 refers to
+    // the calling thread.
+    // SAFETY: setpriority has no memory safety requirements.
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, niceness) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+#[allow(clippy::unnecessary_wraps)]
+fn set_thread_niceness(_niceness: i32) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// An error from opening a window.
+#[derive(Debug)]
+pub enum OpenError {
+    /// winit failed to open the window.
+    Os(OsError),
+    /// The thread for the window's behavior could not be spawned.
+    Thread(std::io::Error),
+}
+
+impl From<OsError> for OpenError {
+    fn from(error: OsError) -> Self {
+        Self::Os(error)
+    }
+}
+
+impl std::fmt::Display for OpenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Os(error) => write!(f, "error opening window: {error}"),
+            Self::Thread(error) => write!(f, "error spawning window thread: {error}"),
         }
     }
 }
 
+impl std::error::Error for OpenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Os(error) => Some(error),
+            Self::Thread(error) => Some(error),
+        }
+    }
+}
+
+/// A tag that identifies a window.
+///
+/// Tags can either be a name, created with [`WindowTag::named`] or converted
+/// from a string, or a type, created with [`WindowTag::of`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct WindowTag(TagKind);
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+enum TagKind {
+    Named(Cow<'static, str>),
+    Typed { id: TypeId, name: &'static str },
+}
+
+impl WindowTag {
+    /// Returns a tag with the given name.
+    #[must_use]
+    pub fn named(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(TagKind::Named(name.into()))
+    }
+
+    /// Returns a tag for the type `T`.
+    #[must_use]
+    pub fn of<T>() -> Self
+    where
+        T: 'static,
+    {
+        Self(TagKind::Typed {
+            id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+        })
+    }
+
+    /// Returns the name of this tag. For tags created with
+    /// [`WindowTag::of`], this is the name of the type.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match &self.0 {
+            TagKind::Named(name) => name,
+            TagKind::Typed { name, .. } => name,
+        }
+    }
+}
+
+impl From<&'static str> for WindowTag {
+    fn from(name: &'static str) -> Self {
+        Self::named(name)
+    }
+}
+
+impl From<String> for WindowTag {
+    fn from(name: String) -> Self {
+        Self::named(name)
+    }
+}
+
+impl From<&WindowTag> for WindowTag {
+    fn from(tag: &WindowTag) -> Self {
+        tag.clone()
+    }
+}
+
 impl<'a, Behavior, Application, AppMessage> WindowBuilder<'a, Behavior, Application, AppMessage>
 where
     Behavior: self::WindowBehavior<AppMessage>,
@@ -203,87 +720,336 @@ where
         Self {
             owner,
             context,
+            restart_context: None,
             attributes: WindowAttributes::default(),
         }
     }
 
+    /// Allows this window to be restarted after its behavior panics, if the
+    /// app's [`PanicPolicy`](crate::PanicPolicy) is
+    /// [`Restart`](crate::PanicPolicy::Restart).
+    ///
+    /// When restarting, `restart_context` is invoked to create the context
+    /// passed to [`WindowBehavior::initialize`] for the new behavior.
+    #[must_use]
+    pub fn restart_with<F>(mut self, restart_context: F) -> Self
+    where
+        F: FnMut() -> Behavior::Context + Send + 'static,
+    {
+        self.restart_context = Some(Box::new(restart_context));
+        self
+    }
+
     /// Opens the window, if the application is still running or has not started
-    /// running. The events of the window will be processed in a thread spawned
-    /// by this function.
+    /// running. The events of the window will be processed on the thread
+    /// selected by [`WindowAttributes::execution`].
     ///
-    /// If the application has shut down, this function returns None.
+    /// If the application has shut down, this function returns None. This
+    /// function also returns None when called from the event loop thread once
+    /// the app is running, as the event loop can't open the window until the
+    /// current callback returns.
     ///
     /// # Errors
     ///
-    /// This function returns any error that winit may return from attempting to
-    /// open a window.
-    pub fn open(mut self) -> Result<Option<Window<AppMessage::Window>>, winit::error::OsError> {
-        // The window's thread shouldn't ever block for long periods of time. To
-        // avoid a "frozen" window causing massive memory allocations, we'll use
-        // a fixed-size channel and be cautious to not block the main event loop
-        // by always using try_send.
-        let (sender, receiver) = mpsc::sync_channel(65536);
-        let sender = Arc::new(sender);
-        let app = self.owner.as_application().app();
-        let show_after_init = (self.attributes.delay_visible
-            && std::mem::replace(&mut self.attributes.visible, false))
-        .then_some(self.attributes.active);
+    /// This function returns an error if the window's thread could not be
+    /// spawned, or any error that winit may return from attempting to open a
+    /// window.
+    pub fn open(self) -> Result<Option<Window<AppMessage::Window>>, OpenError>
+    where
+        Behavior::Context: Send,
+    {
+        let (owner, attributes, setup, context, restart_context) = self.prepare();
+        let sender = setup.messages.0.clone();
+        let workers = setup.app.workers.clone();
 
-        let Some(winit) = self.owner.as_application_mut().open(
-            self.attributes,
-            sender.clone(),
-            Box::new({
+        let spawn: Box<dyn FnOnce(RunningWindow<AppMessage>) -> _ + Send> = match attributes
+            .execution
+        {
+            ExecutionMode::Dedicated => {
+                // The thread is spawned before the window is opened so
+                // that failing to spawn it can be reported to the caller.
+                let (window_sender, window_receiver) = mpsc::sync_channel(1);
+                let thread_attributes = attributes.thread.clone();
+                thread_attributes
+                    .builder()
+                    .spawn(move || {
+                        // If the window fails to open, the sender is
+                        // dropped without sending.
+                        let Ok(window) = window_receiver.recv() else {
+                            return;
+                        };
+                        thread_attributes.setup();
+                        WindowDriver::<Behavior, AppMessage>::new(window, context, restart_context)
+                            .run_dedicated();
+                    })
+                    .map_err(OpenError::Thread)?;
+                Box::new(move |window| {
+                    let _result = window_sender.send(window);
+                    None
+                })
+            }
+            ExecutionMode::Shared => {
+                // Like dedicated threads, the worker is reserved before the
+                // window is opened so that spawning it can fail here.
+                let reservation = workers.reserve().map_err(OpenError::Thread)?;
                 let sender = sender.clone();
-                move |opened| {
-                    let winit = opened.winit().expect("just opened");
-                    let running_window = RunningWindow {
-                        messages: (sender, receiver),
-                        responses: mpsc::sync_channel(1),
-                        app,
-                        occluded: winit.is_visible().unwrap_or(false),
-                        focused: winit.has_focus(),
-                        inner_size: winit.inner_size(),
-                        outer_size: winit.outer_size(),
-                        inner_position: winit.inner_position().unwrap_or_default(),
-                        outer_position: winit.outer_position().unwrap_or_default(),
-                        scale: winit.scale_factor(),
-                        theme: winit.theme().unwrap_or(Theme::Dark),
-                        window: winit,
-                        opened,
-                        next_redraw_target: None,
-                        close: false,
-                        modifiers: Modifiers::default(),
-                        cursor_position: None,
-                        mouse_buttons: HashSet::default(),
-                        keys: HashSet::default(),
-                        show_after_init,
-                    };
-
-                    thread::spawn(move || running_window.run_with::<Behavior>(self.context));
-                }
+                Box::new(move |window| {
+                    reservation.assign(
+                        &sender,
+                        Box::new(move || {
+                            Box::new(WindowDriver::<Behavior, AppMessage>::new(
+                                window,
+                                context,
+                                restart_context,
+                            ))
+                        }),
+                    );
+                    None
+                })
+            }
+            ExecutionMode::EventLoop => Box::new(move |window| {
+                Some(Box::new(WindowDriver::<Behavior, AppMessage>::new(
+                    window,
+                    context,
+                    restart_context,
+                )) as Box<dyn DriveWindow<AppMessage>>)
             }),
+        };
+
+        let Some(winit) = owner.as_application_mut().open(
+            attributes,
+            sender.clone(),
+            Box::new(move |opened| spawn(setup.into_running(opened))),
         )?
         else {
             return Ok(None);
         };
-        let window = Window {
-            opened: winit.clone(),
-            sender: Arc::downgrade(&sender),
+        Ok(Some(Window::new(winit, &sender)))
+    }
+
+    /// Opens the window with its behavior pinned to the event loop thread,
+    /// regardless of [`WindowAttributes::execution`].
+    ///
+    /// Unlike [`open()`](Self::open), the behavior's
+    /// [`Context`](WindowBehavior::Context) is not required to be `Send`,
+    /// allowing types that must remain on the main thread to be provided to
+    /// the behavior. The window's events are delivered directly to the
+    /// behavior by the event loop. See [`ExecutionMode::EventLoop`] for more
+    /// information.
+    ///
+    /// The window is opened once the event loop has finished its current
+    /// callback, but a handle to it is returned immediately. Messages sent
+    /// through the handle are queued until the window opens, and
+    /// [`Window::id()`] returns None until then. If the window fails to open,
+    /// the handle is closed and the error is reported to
+    /// [`PendingApp::on_window_open_failed`].
+    ///
+    /// # Errors
+    ///
+    /// The error type matches [`open()`](Self::open). As the window is opened
+    /// after this function returns, errors opening it are currently reported
+    /// to [`PendingApp::on_window_open_failed`] instead of being returned.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called on a thread other than the one the app
+    /// was created on.
+    pub fn open_pinned(mut self) -> Result<Option<Window<AppMessage::Window>>, OpenError> {
+        self.attributes.execution = ExecutionMode::EventLoop;
+        let (owner, attributes, setup, context, restart_context) = self.prepare();
+        let sender = setup.messages.0.clone();
+
+        let Some(winit) = owner.as_application_mut().open_pinned(
+            attributes,
+            sender.clone(),
+            Box::new(move |opened| {
+                Some(Box::new(WindowDriver::<Behavior, AppMessage>::new(
+                    setup.into_running(opened),
+                    context,
+                    restart_context,
+                )))
+            }),
+        )?
+        else {
+            return Ok(None);
         };
+        Ok(Some(Window::new(winit, &sender)))
+    }
 
-        Ok(Some(window))
+    #[allow(clippy::type_complexity)]
+    fn prepare(
+        mut self,
+    ) -> (
+        &'a mut Application,
+        WindowAttributes,
+        WindowSetup<AppMessage>,
+        Behavior::Context,
+        Option<ContextFactory<Behavior::Context>>,
+    ) {
+        let (sender, receiver) = WindowSender::queue();
+        let show_after_init = (self.attributes.delay_visible
+            && std::mem::replace(&mut self.attributes.visible, false))
+        .then_some(self.attributes.active);
+        let setup = WindowSetup {
+            messages: (Arc::new(sender), receiver),
+            app: self.owner.as_application().app(),
+            show_after_init,
+            tag: self.attributes.tag.clone(),
+            runtime_error_action: self.attributes.runtime_error_action,
+            execution: self.attributes.execution,
+        };
+        (
+            self.owner,
+            self.attributes,
+            setup,
+            self.context,
+            self.restart_context,
+        )
     }
 }
 
-type SyncArcChannel<T> = (Arc<mpsc::SyncSender<T>>, mpsc::Receiver<T>);
+/// The state needed to create a [`RunningWindow`] once its window has been
+/// opened.
+struct WindowSetup<AppMessage>
+where
+    AppMessage: Message,
+{
+    messages: WindowChannel<AppMessage::Window>,
+    app: App<AppMessage>,
+    show_after_init: Option<bool>,
+    tag: Option<WindowTag>,
+    runtime_error_action: RuntimeErrorAction,
+    execution: ExecutionMode,
+}
+
+impl<AppMessage> WindowSetup<AppMessage>
+where
+    AppMessage: Message,
+{
+    fn into_running(self, opened: OpenedWindow) -> RunningWindow<AppMessage> {
+        let winit = opened.winit().expect("just opened");
+        if self.execution == ExecutionMode::EventLoop {
+            // Messages sent from the event loop thread are processed before
+            // the event loop waits again.
+            let window_id = winit.id();
+            let event_loop_thread = self.app.event_loop_thread;
+            let proxy = Mutex::new(self.app.proxy.clone());
+            self.messages.0.set_waker(move || {
+                if thread::current().id() != event_loop_thread {
+                    let _result = proxy
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .send_event(EventLoopMessage::WakeWindow(window_id));
+                }
+            });
+        }
+
+        RunningWindow {
+            messages: self.messages,
+            responses: mpsc::sync_channel(1),
+            app: self.app,
+            occluded: winit.is_visible().unwrap_or(false),
+            focused: winit.has_focus(),
+            inner_size: winit.inner_size(),
+            outer_size: winit.outer_size(),
+            inner_position: winit.inner_position().unwrap_or_default(),
+            outer_position: winit.outer_position().unwrap_or_default(),
+            scale: winit.scale_factor(),
+            theme: winit.theme().unwrap_or(Theme::Dark),
+            window: winit,
+            opened,
+            next_redraw_target: None,
+            close: false,
+            close_reason: CloseReason::Programmatic,
+            modifiers: Modifiers::default(),
+            cursor_position: None,
+            cursor_positions: HashMap::default(),
+            cursor_sequence: 0,
+            mouse_buttons: HashMap::default(),
+            keys: HashMap::default(),
+            lock_keys: LockKeys::default(),
+            synthetic_event: false,
+            show_after_init: self.show_after_init,
+            tag: self.tag,
+            runtime_error_action: self.runtime_error_action,
+            retry_resize: false,
+            scale_size_writer: None,
+            scroll: None,
+            event_time: None,
+            pending_input: None,
+            statistics: WindowStatistics::default(),
+        }
+    }
+}
+
+type WindowChannel<T> = (Arc<WindowSender<T>>, WindowReceiver<T>);
 type SyncChannel<T> = (mpsc::SyncSender<T>, mpsc::Receiver<T>);
 
+/// Returns the sender the event loop should answer an app message on.
+///
+/// Windows driven by the event loop never wait for the response, so they are
+/// given a channel nobody reads from. Reusing the shared channel would leave
+/// the first response in it, and the event loop would block forever sending
+/// the second.
+fn response_sender<T>(responses: &SyncChannel<T>, on_event_loop: bool) -> mpsc::SyncSender<T> {
+    if on_event_loop {
+        mpsc::sync_channel(1).0
+    } else {
+        responses.0.clone()
+    }
+}
+
 enum HandleMessageResult {
     Ok,
     RedrawRequired(RedrawGuard),
     Destroyed,
 }
 
+/// Statistics about the frames a window has drawn.
+///
+/// Input latency is measured from the time the event loop received the oldest
+/// input event handled before a frame until the window's behavior finished
+/// drawing that frame.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct WindowStatistics {
+    /// The number of frames the window has drawn. Frames whose redraw
+    /// returned an error are not counted.
+    pub frames: u64,
+    /// The number of frames drawn after handling at least one input event.
+    pub input_frames: u64,
+    /// The input latency of the most recent frame drawn after handling input.
+    pub last_input_latency: Option<Duration>,
+    /// The largest input latency measured.
+    pub max_input_latency: Option<Duration>,
+    /// The sum of the input latency of every input frame.
+    pub total_input_latency: Duration,
+}
+
+impl WindowStatistics {
+    /// Returns the average input latency, or `None` if no frames have been
+    /// drawn after handling input.
+    #[must_use]
+    pub fn average_input_latency(&self) -> Option<Duration> {
+        let frames = u32::try_from(self.input_frames).ok().filter(|f| *f > 0)?;
+        Some(self.total_input_latency / frames)
+    }
+
+    fn frame_drawn(&mut self, oldest_input: Option<Instant>) {
+        self.frames += 1;
+        let Some(oldest_input) = oldest_input else {
+            return;
+        };
+        let latency = oldest_input.elapsed();
+        self.input_frames += 1;
+        self.last_input_latency = Some(latency);
+        self.max_input_latency = Some(
+            self.max_input_latency
+                .map_or(latency, |max| max.max(latency)),
+        );
+        self.total_input_latency += latency;
+    }
+}
+
 /// A window that is running in its own thread.
 #[allow(clippy::struct_excessive_bools)] // stop judging me clippy!
 pub struct RunningWindow<AppMessage>
@@ -293,7 +1059,7 @@ where
     window: Arc<winit::window::Window>,
     opened: OpenedWindow,
     next_redraw_target: Option<RedrawTarget>,
-    messages: SyncArcChannel<WindowMessage<AppMessage::Window>>,
+    messages: WindowChannel<AppMessage::Window>,
     responses: SyncChannel<AppMessage::Response>,
     app: App<AppMessage>,
     inner_size: PhysicalSize<u32>,
@@ -301,15 +1067,30 @@ where
     outer_position: PhysicalPosition<i32>,
     inner_position: PhysicalPosition<i32>,
     cursor_position: Option<PhysicalPosition<f64>>,
-    mouse_buttons: HashSet<MouseButton>,
-    keys: HashSet<PhysicalKey>,
+    /// The position of each cursor over the window, along with the value of
+    /// `cursor_sequence` when it last moved.
+    cursor_positions: HashMap<DeviceId, (u64, PhysicalPosition<f64>)>,
+    cursor_sequence: u64,
+    mouse_buttons: HashMap<MouseButton, HashSet<DeviceId>>,
+    keys: HashMap<PhysicalKey, (DeviceId, KeyEvent)>,
+    lock_keys: LockKeys,
+    synthetic_event: bool,
     scale: f64,
     close: bool,
+    close_reason: CloseReason,
     occluded: bool,
     focused: bool,
     theme: Theme,
     modifiers: Modifiers,
     show_after_init: Option<bool>,
+    tag: Option<WindowTag>,
+    runtime_error_action: RuntimeErrorAction,
+    retry_resize: bool,
+    scale_size_writer: Option<InnerSizeWriter>,
+    scroll: Option<ScrollProcessor>,
+    event_time: Option<Instant>,
+    pending_input: Option<Instant>,
+    statistics: WindowStatistics,
 }
 
 impl<AppMessage> RunningWindow<AppMessage>
@@ -325,10 +1106,13 @@ where
     /// Returns a handle to this window.
     #[must_use]
     pub fn handle(&self) -> Window<AppMessage::Window> {
-        Window {
-            opened: self.opened.clone(),
-            sender: Arc::downgrade(&self.messages.0),
-        }
+        Window::new(self.opened.clone(), &self.messages.0)
+    }
+
+    /// Returns the tag this window was opened with.
+    #[must_use]
+    pub const fn tag(&self) -> Option<&WindowTag> {
+        self.tag.as_ref()
     }
 
     /// Returns the target for when the window will be redrawn.
@@ -350,12 +1134,8 @@ where
         // Make sure this new scheduled time isn't further out than our current target.
         match self.next_redraw_target {
             Some(RedrawTarget::Immediate) => return,
-            Some(RedrawTarget::Scheduled(at)) => {
-                if at < instant {
-                    return;
-                }
-            }
-            None => {}
+            Some(RedrawTarget::Scheduled(at)) if at < instant => return,
+            Some(RedrawTarget::Scheduled(_)) | None => {}
         }
 
         self.next_redraw_target = Some(RedrawTarget::Scheduled(instant));
@@ -407,6 +1187,32 @@ where
         result
     }
 
+    /// Requests the inner size the window should be resized to after its scale
+    /// factor changes.
+    ///
+    /// This function only has an effect when called from
+    /// [`WindowBehavior::scale_factor_changed`]. By default, the window is
+    /// resized to the size suggested by the operating system. Logical sizes
+    /// are converted to pixels using the new scale factor. The size is applied
+    /// by the event loop once `scale_factor_changed` returns.
+    ///
+    /// Returns true if the size will be applied. Returns false if the event
+    /// loop stopped waiting for the window before it handled the change, in
+    /// which case the suggested size is used. See
+    /// [`WindowBehavior::scale_factor_changed`] for more information.
+    pub fn request_scaled_inner_size(&mut self, new_size: impl Into<Size>) -> bool {
+        let Some(writer) = &mut self.scale_size_writer else {
+            return false;
+        };
+        let new_size = new_size.into().to_physical::<u32>(self.scale);
+        if writer.request_inner_size(new_size).is_ok() {
+            self.inner_size = new_size;
+            true
+        } else {
+            false
+        }
+    }
+
     /// Returns the current outer size of the window, in pixels.
     #[must_use]
     pub const fn outer_size(&self) -> PhysicalSize<u32> {
@@ -432,145 +1238,295 @@ where
 
     /// Returns the position of the cursor relative to the window's upper-left
     /// corner, in pixels.
+    ///
+    /// When multiple pointing devices are in use, this is the position of the
+    /// cursor that moved most recently. When that cursor leaves the window,
+    /// the remaining cursor that moved most recently is used instead.
     #[must_use]
     pub const fn cursor_position(&self) -> Option<PhysicalPosition<f64>> {
         self.cursor_position
     }
 
+    /// Returns the position of `device`'s cursor relative to the window's
+    /// upper-left corner, in pixels.
+    #[must_use]
+    pub fn cursor_position_for(&self, device: DeviceId) -> Option<PhysicalPosition<f64>> {
+        self.cursor_positions
+            .get(&device)
+            .map(|(_, position)| *position)
+    }
+
+    /// Returns an iterator of the positions of each cursor that is currently
+    /// over the window.
+    ///
+    /// This iterator does not guarantee any specific order.
+    pub fn cursor_positions(&self) -> impl Iterator<Item = (DeviceId, PhysicalPosition<f64>)> + '_ {
+        self.cursor_positions
+            .iter()
+            .map(|(device, (_, position))| (*device, *position))
+    }
+
     /// Returns the current scale factor for the window.
     #[must_use]
     pub const fn scale(&self) -> f64 {
         self.scale
     }
 
-    /// Returns true if the window is currently invisible, hidden behind other
-    /// windows, minimized, or otherwise hidden from the user's view.
-    #[must_use]
-    pub const fn occluded(&self) -> bool {
-        self.occluded
+    /// Returns the time the event loop received the event currently being
+    /// handled.
+    ///
+    /// This can be used to determine how long an event waited before the
+    /// window's behavior handled it. Returns `None` when the behavior is not
+    /// handling an event from the event loop, such as while redrawing.
+    #[must_use]
+    pub const fn current_event_time(&self) -> Option<Instant> {
+        self.event_time
+    }
+
+    /// Returns statistics about the frames this window has drawn, including
+    /// the latency between receiving input and drawing a frame in response.
+    #[must_use]
+    pub const fn statistics(&self) -> &WindowStatistics {
+        &self.statistics
+    }
+
+    /// Enables or disables scroll processing for this window.
+    ///
+    /// When enabled, each scroll event is converted into a delta measured in
+    /// physical pixels and delivered to [`WindowBehavior::scrolled`]. Scrolling
+    /// measured in lines is converted using
+    /// [`ScrollSettings::line_height`] and the window's scale factor. If
+    /// [`ScrollSettings::momentum`] is true, scrolling continues after a
+    /// touchpad gesture ends, with each step of the momentum delivered before
+    /// the window is redrawn.
+    ///
+    /// Scroll processing is disabled by default.
+    pub fn set_scroll_processing(&mut self, settings: Option<ScrollSettings>) {
+        self.scroll = settings.map(ScrollProcessor::new);
+    }
+
+    /// Returns the settings scroll events are processed with, or `None` if
+    /// scroll processing is disabled.
+    #[must_use]
+    pub fn scroll_processing(&self) -> Option<&ScrollSettings> {
+        self.scroll.as_ref().map(ScrollProcessor::settings)
+    }
+
+    /// Returns true if the window is currently invisible, hidden behind other
+    /// windows, minimized, or otherwise hidden from the user's view.
+    #[must_use]
+    pub const fn occluded(&self) -> bool {
+        self.occluded
+    }
+
+    /// Returns true if the window is currently focused for keyboard input.
+    #[must_use]
+    pub const fn focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Returns the current theme of the window.
+    #[must_use]
+    pub const fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    /// Returns the current state of the keyboard modifier keys.
+    #[must_use]
+    pub const fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    fn initialize<Behavior>(
+        &mut self,
+        context: Behavior::Context,
+    ) -> Result<Behavior, AppMessage::Error>
+    where
+        Behavior: self::WindowBehavior<AppMessage>,
+    {
+        let mut behavior = Behavior::initialize(self, context)?;
+        // When it takes a while for a graphics stack to initialize, we can
+        // avoid showing a blank window due to our multi-threaded event
+        // handling by not showing the window until the graphics stack has
+        // been initialized.
+        if let Some(activate) = self.show_after_init.take() {
+            self.next_redraw_target = None;
+            let _drawn = self.redraw(&mut behavior);
+            self.window.set_visible(true);
+            if activate {
+                self.window.focus_window();
+            }
+        }
+
+        behavior.initialized(self);
+        Ok(behavior)
+    }
+
+    /// Processes the messages that have been received and redraws the window
+    /// if needed, without blocking.
+    ///
+    /// Returns when the window should be woken next, or `Err` if the window
+    /// has finished.
+    fn process_available<Behavior>(
+        &mut self,
+        behavior: &mut Behavior,
+    ) -> Result<Option<Instant>, ()>
+    where
+        Behavior: self::WindowBehavior<AppMessage>,
+    {
+        if self.messages.1.is_disconnected() {
+            // The event loop forcibly closed this window after it stopped
+            // responding.
+            return Err(());
+        }
+        loop {
+            let Some(message) = self.messages.1.try_recv() else {
+                if !self.redraw_due() {
+                    return Ok(self.wake_at());
+                }
+                return self.redraw_frame(behavior, None);
+            };
+            if self.messages.0.set_responding() {
+                let _result = self
+                    .app
+                    .proxy
+                    .send_event(EventLoopMessage::WindowResponding(self.window.id()));
+            }
+
+            self.event_time = match &message {
+                WindowMessage::Event(event, received) => {
+                    if event.is_input() {
+                        self.pending_input.get_or_insert(*received);
+                    }
+                    Some(*received)
+                }
+                _ => None,
+            };
+            let result = self.handle_message(message, behavior);
+            self.event_time = None;
+            match result {
+                HandleMessageResult::Ok => {}
+                HandleMessageResult::RedrawRequired(guard) => {
+                    return self.redraw_frame(behavior, Some(guard));
+                }
+                HandleMessageResult::Destroyed => return Err(()),
+            }
+        }
+    }
+
+    fn redraw_frame<Behavior>(
+        &mut self,
+        behavior: &mut Behavior,
+        guard: Option<RedrawGuard>,
+    ) -> Result<Option<Instant>, ()>
+    where
+        Behavior: self::WindowBehavior<AppMessage>,
+    {
+        self.next_redraw_target = None;
+        self.inner_size = self.window.inner_size();
+        self.step_scroll_momentum(behavior);
+        if std::mem::take(&mut self.retry_resize) {
+            self.resized(behavior);
+        }
+        if self.redraw(behavior) {
+            // Input handled before a failed frame is still waiting to be
+            // displayed, so its latency is measured by the next frame drawn.
+            self.statistics.frame_drawn(self.pending_input.take());
+        }
+        drop(guard);
+
+        // Closing the window requests a redraw, ensuring the queued messages
+        // are processed and a final frame is drawn before exiting.
+        if self.close {
+            Err(())
+        } else {
+            Ok(self.messages.1.next_wake(self.wake_at()))
+        }
+    }
+
+    fn step_scroll_momentum<Behavior>(&mut self, behavior: &mut Behavior)
+    where
+        Behavior: self::WindowBehavior<AppMessage>,
+    {
+        let now = Instant::now();
+        let Some((device_id, delta, phase)) =
+            self.scroll.as_mut().and_then(|scroll| scroll.step(now))
+        else {
+            return;
+        };
+        if phase == ScrollPhase::Momentum {
+            self.redraw_at(now + RedrawSync::frame_duration(&self.window));
+        }
+        behavior.scrolled(self, device_id, delta, phase);
     }
 
-    /// Returns true if the window is currently focused for keyboard input.
-    #[must_use]
-    pub const fn focused(&self) -> bool {
-        self.focused
+    fn redraw_due(&self) -> bool {
+        match self.next_redraw_target {
+            Some(RedrawTarget::Immediate) => true,
+            Some(RedrawTarget::Scheduled(at)) => at <= Instant::now(),
+            None => false,
+        }
     }
 
-    /// Returns the current theme of the window.
-    #[must_use]
-    pub const fn theme(&self) -> Theme {
-        self.theme
+    fn wake_at(&self) -> Option<Instant> {
+        match self.next_redraw_target {
+            Some(RedrawTarget::Immediate) => Some(Instant::now()),
+            Some(RedrawTarget::Scheduled(at)) => Some(at),
+            None => None,
+        }
     }
 
-    /// Returns the current state of the keyboard modifier keys.
-    #[must_use]
-    pub const fn modifiers(&self) -> Modifiers {
-        self.modifiers
+    /// Blocks until a message is received or `wake_at` has elapsed.
+    fn wait_for_message(&self, wake_at: Option<Instant>) {
+        self.messages.1.wait(wake_at);
     }
 
-    fn run_with<Behavior>(mut self, context: Behavior::Context)
+    /// Redraws the window, returning true if the frame was drawn.
+    fn redraw<Behavior>(&mut self, behavior: &mut Behavior) -> bool
     where
         Behavior: self::WindowBehavior<AppMessage>,
     {
-        let proxy = self.app.proxy.clone();
-        let window_id = self.window.id();
-        // We assert unwind safety here due to internal types on some platforms
-        // in winit use dyn trait objects that do not specify unwind safety.
-        // However, in this situation we are not recovering the window itself.
-        // The panic will cause us to ask winit to close the window. There is no
-        // recovery for a panic inside of a window, the only question is whether
-        // the entire app panics or not.
-        let possible_panic = std::panic::catch_unwind(AssertUnwindSafe(move || {
-            let mut behavior = Behavior::initialize(&mut self, context)?;
-
-            // When it takes a while for a graphics stack to initialize, we can
-            // avoid showing a blank window due to our multi-threaded event
-            // handling by not showing the window until the graphics stack has
-            // been initialized.
-            if let Some(activate) = self.show_after_init {
-                self.next_redraw_target = None;
-                behavior.redraw(&mut self);
-                self.window.set_visible(true);
-                if activate {
-                    self.window.focus_window();
-                }
-            }
-
-            behavior.initialized(&mut self);
-
-            while !self.close {
-                match self.process_messages_until_redraw(&mut behavior) {
-                    Ok(guard) => {
-                        self.next_redraw_target = None;
-                        self.inner_size = self.window.inner_size();
-                        behavior.redraw(&mut self);
-                        drop(guard);
-                    }
-                    Err(()) => break,
-                }
-            }
-            // Do not notify the main thread to close the window until after the
-            // behavior is dropped. This upholds the requirement for RawWindowHandle
-            // by making sure that any resources required by the behavior have had a
-            // chance to be freed.
-            Ok(())
-        }));
-
-        match possible_panic {
-            Ok(Ok(())) => {
-                let _result = proxy.send_event(EventLoopMessage::CloseWindow(window_id));
-            }
-            Ok(Err(init_error)) => {
-                let _result = proxy.send_event(EventLoopMessage::Error(init_error));
-            }
-            Err(panic) => {
-                let _result = proxy.send_event(EventLoopMessage::WindowPanic(window_id));
-                std::panic::resume_unwind(panic)
+        match behavior.try_redraw(self) {
+            Ok(()) => true,
+            Err(error) => {
+                self.runtime_error(error, false);
+                false
             }
         }
     }
 
-    fn process_messages_until_redraw<Behavior>(
-        &mut self,
-        behavior: &mut Behavior,
-    ) -> Result<Option<RedrawGuard>, ()>
+    fn resized<Behavior>(&mut self, behavior: &mut Behavior)
     where
         Behavior: self::WindowBehavior<AppMessage>,
     {
-        loop {
-            let message = match TimeUntilRedraw::from(self.next_redraw_target) {
-                // The scheduled redraw time has already elapsed, or we need to
-                // redraw. Process messages that are already enqueued, but don't
-                // block.
-                TimeUntilRedraw::None => match self.messages.1.try_recv() {
-                    Ok(message) => message,
-                    Err(mpsc::TryRecvError::Disconnected) => return Err(()),
-                    Err(mpsc::TryRecvError::Empty) => return Ok(None),
-                },
-                // We have a scheduled time for the next frame, and it hasn't
-                // elapsed yet.
-                TimeUntilRedraw::Some(duration_remaining) => {
-                    match self.messages.1.recv_timeout(duration_remaining) {
-                        Ok(message) => message,
-                        Err(mpsc::RecvTimeoutError::Timeout) => return Ok(None),
-                        Err(mpsc::RecvTimeoutError::Disconnected) => return Err(()),
-                    }
-                }
-                // No scheduled redraw time, sleep until the next message.
-                TimeUntilRedraw::Indefinite => match self.messages.1.recv() {
-                    Ok(message) => message,
-                    Err(_) => return Err(()),
-                },
-            };
+        if let Err(error) = behavior.try_resized(self) {
+            self.runtime_error(error, true);
+        }
+    }
 
-            match self.handle_message(message, behavior) {
-                HandleMessageResult::Ok => {}
-                HandleMessageResult::RedrawRequired(guard) => return Ok(Some(guard)),
-                HandleMessageResult::Destroyed => return Err(()),
+    fn runtime_error(&mut self, error: AppMessage::Error, while_resizing: bool) {
+        let _result = self.app.send_error_from(error, Some(self.window.id()));
+        match self.runtime_error_action {
+            RuntimeErrorAction::Ignore => {}
+            RuntimeErrorAction::CloseWindow => {
+                self.close();
+                self.close_reason = CloseReason::RuntimeError;
+            }
+            RuntimeErrorAction::RetryNextFrame => {
+                self.retry_resize |= while_resizing;
+                // Waiting a frame keeps a persistent error from redrawing and
+                // reporting errors as fast as the window can loop.
+                self.redraw_in(RedrawSync::frame_duration(&self.window));
             }
         }
     }
 
+    fn prepare_for_restart(&mut self) {
+        self.close = false;
+        self.close_reason = CloseReason::Programmatic;
+        self.set_needs_redraw();
+    }
+
     #[allow(clippy::too_many_lines)] // can't avoid the match
     fn handle_message<Behavior>(
         &mut self,
@@ -582,7 +1538,20 @@ where
     {
         match message {
             WindowMessage::User(user) => behavior.event(self, user),
-            WindowMessage::Event(evt) => match evt {
+            WindowMessage::QuitRequested(request) => {
+                let allowed = behavior.close_requested(self);
+                let _result = self.app.proxy.send_event(EventLoopMessage::QuitResponse {
+                    window_id: self.window.id(),
+                    request,
+                    allowed,
+                });
+            }
+            WindowMessage::QuitCancelled => behavior.quit_cancelled(self),
+            WindowMessage::Quit => {
+                self.close();
+                self.close_reason = CloseReason::Quit;
+            }
+            WindowMessage::Event(evt, _) => match evt {
                 WindowEvent::RedrawRequested(guard) => {
                     self.set_needs_redraw();
                     return HandleMessageResult::RedrawRequired(guard);
@@ -590,17 +1559,28 @@ where
                 WindowEvent::CloseRequested => {
                     if behavior.close_requested(self) {
                         self.close();
+                        self.close_reason = CloseReason::UserRequested;
                     }
                 }
                 WindowEvent::Focused(focused) => {
                     self.focused = focused;
+                    if !focused {
+                        self.release_pressed_input(behavior);
+                        // Lock keys may be toggled while another window has
+                        // focus.
+                        self.lock_keys = LockKeys::default();
+                    }
                     behavior.focus_changed(self);
                 }
                 WindowEvent::Occluded(occluded) => {
                     self.occluded = occluded;
                     behavior.occlusion_changed(self);
                 }
-                WindowEvent::ScaleFactorChanged { scale_factor } => {
+                WindowEvent::ScaleFactorChanged {
+                    scale_factor,
+                    inner_size_writer,
+                    guard,
+                } => {
                     // Ensure both values are updated before any behavior
                     // callbacks are invoked.
                     self.scale = scale_factor;
@@ -608,18 +1588,28 @@ where
                     let new_outer_size = self.window.outer_size();
                     self.inner_size = new_inner_size;
                     self.outer_size = new_outer_size;
+                    self.scale_size_writer = Some(inner_size_writer);
                     behavior.scale_factor_changed(self);
+                    self.scale_size_writer = None;
+                    // Allow the event loop to apply the requested size.
+                    drop(guard);
                     if self.inner_size != new_inner_size || self.outer_size != new_outer_size {
-                        behavior.resized(self);
+                        self.resized(behavior);
                     }
                 }
-                WindowEvent::Resized(new_inner_size) => {
+                WindowEvent::Resized(new_inner_size, guard) => {
                     let new_outer_size = self.window.outer_size();
                     let outer_size_changed = new_outer_size != self.outer_size;
                     self.outer_size = new_outer_size;
                     if outer_size_changed || self.inner_size != new_inner_size {
                         self.inner_size = new_inner_size;
-                        behavior.resized(self);
+                        self.resized(behavior);
+                        if let Some(guard) = guard {
+                            // The event loop is waiting for a frame at the new
+                            // size.
+                            self.set_needs_redraw();
+                            return HandleMessageResult::RedrawRequired(guard);
+                        }
                     }
                 }
                 WindowEvent::Moved(outer_position) => {
@@ -658,7 +1648,11 @@ where
                 } => {
                     match event.state {
                         ElementState::Pressed => {
-                            self.keys.insert(event.physical_key);
+                            if !is_synthetic {
+                                self.lock_keys.key_pressed(&event, self.modifiers);
+                            }
+                            self.keys
+                                .insert(event.physical_key, (device_id, event.clone()));
                         }
                         ElementState::Released => {
                             self.keys.remove(&event.physical_key);
@@ -678,13 +1672,21 @@ where
                     position,
                 } => {
                     self.cursor_position = Some(position);
+                    self.cursor_sequence += 1;
+                    self.cursor_positions
+                        .insert(device_id, (self.cursor_sequence, position));
                     behavior.cursor_moved(self, device_id, position);
                 }
                 WindowEvent::CursorEntered { device_id } => {
                     behavior.cursor_entered(self, device_id);
                 }
                 WindowEvent::CursorLeft { device_id } => {
-                    self.cursor_position = None;
+                    self.cursor_positions.remove(&device_id);
+                    self.cursor_position = self
+                        .cursor_positions
+                        .values()
+                        .max_by_key(|(sequence, _)| *sequence)
+                        .map(|(_, position)| *position);
                     behavior.cursor_left(self, device_id);
                 }
                 WindowEvent::MouseWheel {
@@ -693,6 +1695,7 @@ where
                     phase,
                 } => {
                     behavior.mouse_wheel(self, device_id, delta, phase);
+                    self.process_scroll(behavior, device_id, delta, phase);
                 }
                 WindowEvent::MouseInput {
                     device_id,
@@ -701,10 +1704,18 @@ where
                 } => {
                     match state {
                         ElementState::Pressed => {
-                            self.mouse_buttons.insert(button);
+                            self.mouse_buttons
+                                .entry(button)
+                                .or_default()
+                                .insert(device_id);
                         }
                         ElementState::Released => {
-                            self.mouse_buttons.remove(&button);
+                            if let Some(devices) = self.mouse_buttons.get_mut(&button) {
+                                devices.remove(&device_id);
+                                if devices.is_empty() {
+                                    self.mouse_buttons.remove(&button);
+                                }
+                            }
                         }
                     }
                     behavior.mouse_input(self, device_id, state, button);
@@ -757,6 +1768,77 @@ where
         HandleMessageResult::Ok
     }
 
+    /// Converts a scroll event using the window's scroll processor, if
+    /// enabled, and delivers the result to [`WindowBehavior::scrolled`].
+    fn process_scroll<Behavior>(
+        &mut self,
+        behavior: &mut Behavior,
+        device_id: DeviceId,
+        delta: MouseScrollDelta,
+        phase: TouchPhase,
+    ) where
+        Behavior: self::WindowBehavior<AppMessage>,
+    {
+        let scale = self.scale;
+        let Some(scroll) = &mut self.scroll else {
+            return;
+        };
+        let Scrolled {
+            interrupted,
+            delta,
+            phase,
+        } = scroll.scroll(device_id, delta, phase, scale, Instant::now());
+        let momentum = scroll.momentum_active();
+
+        if let Some(interrupted) = interrupted {
+            behavior.scrolled(
+                self,
+                interrupted,
+                PhysicalPosition::default(),
+                ScrollPhase::MomentumEnded,
+            );
+        }
+        behavior.scrolled(self, device_id, delta, phase);
+        if momentum {
+            self.set_needs_redraw();
+        }
+    }
+
+    /// Releases all keys and mouse buttons that are still pressed, invoking
+    /// the behavior's callbacks with synthetic release events.
+    ///
+    /// Not all platforms deliver release events for keys and buttons that are
+    /// held when a window loses focus.
+    fn release_pressed_input<Behavior>(&mut self, behavior: &mut Behavior)
+    where
+        Behavior: self::WindowBehavior<AppMessage>,
+    {
+        self.synthetic_event = true;
+        for (_, (device_id, mut event)) in std::mem::take(&mut self.keys) {
+            event.state = ElementState::Released;
+            event.repeat = false;
+            event.text = None;
+            behavior.keyboard_input(self, device_id, event, true);
+        }
+        for (button, devices) in std::mem::take(&mut self.mouse_buttons) {
+            for device_id in devices {
+                behavior.mouse_input(self, device_id, ElementState::Released, button);
+            }
+        }
+        self.synthetic_event = false;
+    }
+
+    /// Returns true if the event currently being handled was generated by
+    /// this crate rather than being received from the operating system.
+    ///
+    /// When a window loses focus, synthetic release events are delivered to
+    /// [`WindowBehavior::keyboard_input`] and [`WindowBehavior::mouse_input`]
+    /// for each key and mouse button that is still pressed.
+    #[must_use]
+    pub const fn is_synthetic_event(&self) -> bool {
+        self.synthetic_event
+    }
+
     /// Sets this window to close as soon as possible.
     pub fn close(&mut self) {
         self.close = true;
@@ -767,26 +1849,105 @@ where
     ///
     /// This iterator does not guarantee any specific order.
     pub fn pressed_keys(&self) -> impl Iterator<Item = PhysicalKey> + '_ {
-        self.keys.iter().copied()
+        self.keys.keys().copied()
     }
 
     /// Returns true if the given key code is currently pressed.
     #[must_use]
     pub fn key_pressed(&self, key: &PhysicalKey) -> bool {
-        self.keys.contains(key)
+        self.keys.contains_key(key)
+    }
+
+    /// Returns an iterator of the logical keys that are currently pressed,
+    /// along with the location of the key on the keyboard.
+    ///
+    /// The logical key is the key that was reported when the key was pressed.
+    /// This iterator does not guarantee any specific order.
+    pub fn pressed_logical_keys(&self) -> impl Iterator<Item = (&Key, KeyLocation)> + '_ {
+        self.keys
+            .values()
+            .map(|(_, event)| (&event.logical_key, event.location))
+    }
+
+    /// Returns true if the given logical key is currently pressed at any
+    /// location.
+    #[must_use]
+    pub fn logical_key_pressed(&self, key: &Key) -> bool {
+        self.keys
+            .values()
+            .any(|(_, event)| &event.logical_key == key)
+    }
+
+    /// Returns true if the given logical key is currently pressed at
+    /// `location`.
+    ///
+    /// This can be used to distinguish between the left and right variants
+    /// of keys such as shift.
+    #[must_use]
+    pub fn logical_key_pressed_at(&self, key: &Key, location: KeyLocation) -> bool {
+        self.keys
+            .values()
+            .any(|(_, event)| &event.logical_key == key && event.location == location)
+    }
+
+    /// Returns whether caps lock is enabled, or `None` if it is not yet known.
+    ///
+    /// Lock key states are not reported by all platforms. Instead, the state
+    /// is inferred from the keyboard input received by this window, such as
+    /// whether letters are typed in upper case while shift is not held.
+    ///
+    /// This is a heuristic: keyboard layouts and input methods that produce
+    /// unexpected text can cause the inferred state to be wrong until more
+    /// input is received. The state is forgotten when the window loses focus,
+    /// as lock keys may be toggled while another window has focus.
+    #[must_use]
+    pub const fn caps_lock(&self) -> Option<bool> {
+        self.lock_keys.caps
+    }
+
+    /// Returns whether num lock is enabled, or `None` if it is not yet known.
+    ///
+    /// Like [`caps_lock()`](Self::caps_lock), this state is inferred from
+    /// keyboard input and may be inaccurate. Scroll lock is not tracked, as it
+    /// does not affect any input its state could be inferred from.
+    #[must_use]
+    pub const fn num_lock(&self) -> Option<bool> {
+        self.lock_keys.num
     }
 
     /// Returns an iterator of the currently pressed mouse buttons.
     ///
     /// This iterator does not guarantee any specific order.
     pub fn pressed_mouse_buttons(&self) -> impl Iterator<Item = MouseButton> + '_ {
-        self.mouse_buttons.iter().copied()
+        self.mouse_buttons.keys().copied()
     }
 
-    /// Returns true if the button is currently pressed.
+    /// Returns true if the button is currently pressed on any device.
     #[must_use]
     pub fn mouse_button_pressed(&self, button: &MouseButton) -> bool {
-        self.mouse_buttons.contains(button)
+        self.mouse_buttons.contains_key(button)
+    }
+
+    /// Returns an iterator of the mouse buttons currently pressed on
+    /// `device`.
+    ///
+    /// This iterator does not guarantee any specific order.
+    pub fn pressed_mouse_buttons_for(
+        &self,
+        device: DeviceId,
+    ) -> impl Iterator<Item = MouseButton> + '_ {
+        self.mouse_buttons
+            .iter()
+            .filter(move |(_, devices)| devices.contains(&device))
+            .map(|(button, _)| *button)
+    }
+
+    /// Returns true if the button is currently pressed on `device`.
+    #[must_use]
+    pub fn mouse_button_pressed_for(&self, device: DeviceId, button: &MouseButton) -> bool {
+        self.mouse_buttons
+            .get(button)
+            .is_some_and(|devices| devices.contains(&device))
     }
 }
 
@@ -799,20 +1960,26 @@ where
     }
 
     fn send(&mut self, message: AppMessage) -> Option<<AppMessage as Message>::Response> {
+        let on_event_loop = self.app.on_event_loop_thread();
         self.app
             .proxy
             .send_event(EventLoopMessage::User {
                 message,
-                response_sender: self.responses.0.clone(),
+                response_sender: response_sender(&self.responses, on_event_loop),
             })
             .ok()?;
+        if on_event_loop {
+            // The event loop can't handle the message until this window
+            // returns control to it.
+            return None;
+        }
         self.responses.1.recv().ok()
     }
     fn send_error(
         &mut self,
         error: <AppMessage as Message>::Error,
     ) -> Result<(), winit::event_loop::EventLoopClosed<<AppMessage as Message>::Error>> {
-        self.app.send_error(error)
+        self.app.send_error_from(error, Some(self.window.id()))
     }
 }
 
@@ -823,54 +1990,253 @@ where
     fn open(
         &mut self,
         attrs: WindowAttributes,
-        sender: Arc<mpsc::SyncSender<WindowMessage<AppMessage::Window>>>,
-        spawner: WindowSpawner,
+        sender: Arc<WindowSender<AppMessage::Window>>,
+        spawner: WindowSpawner<AppMessage>,
     ) -> Result<Option<OpenedWindow>, OsError> {
-        let (open_sender, open_receiver) = mpsc::sync_channel(1);
-        if self
-            .app
-            .proxy
-            .send_event(EventLoopMessage::OpenWindow {
-                attrs,
-                sender,
-                open_sender,
-                spawner,
-            })
-            .is_ok()
-        {
-            if let Ok(window) = open_receiver.recv() {
-                return window.map(Some);
+        private::ApplicationSealed::open(&mut self.app.clone(), attrs, sender, spawner)
+    }
+
+    fn open_pinned(
+        &mut self,
+        attrs: WindowAttributes,
+        sender: Arc<WindowSender<AppMessage::Window>>,
+        spawner: LocalSpawner<AppMessage>,
+    ) -> Result<Option<OpenedWindow>, OsError> {
+        private::ApplicationSealed::open_pinned(&mut self.app.clone(), attrs, sender, spawner)
+    }
+}
+
+/// Drives a [`WindowBehavior`] for a [`RunningWindow`], initializing and
+/// restarting the behavior as needed.
+struct WindowDriver<Behavior, AppMessage>
+where
+    Behavior: self::WindowBehavior<AppMessage>,
+    AppMessage: Message,
+{
+    window: RunningWindow<AppMessage>,
+    behavior: BehaviorState<Behavior, Behavior::Context>,
+    restart_context: Option<ContextFactory<Behavior::Context>>,
+    restarts: u32,
+}
+
+enum BehaviorState<Behavior, Context> {
+    Uninitialized(Context),
+    Running(Behavior),
+    /// Waiting for the event loop to decide whether to restart the behavior
+    /// after it panicked.
+    AwaitingRestart(mpsc::Receiver<Duration>),
+    Restarting(Instant),
+    Exited,
+}
+
+impl<Behavior, AppMessage> WindowDriver<Behavior, AppMessage>
+where
+    Behavior: self::WindowBehavior<AppMessage>,
+    AppMessage: Message,
+{
+    fn new(
+        window: RunningWindow<AppMessage>,
+        context: Behavior::Context,
+        restart_context: Option<ContextFactory<Behavior::Context>>,
+    ) -> Self {
+        Self {
+            window,
+            behavior: BehaviorState::Uninitialized(context),
+            restart_context,
+            restarts: 0,
+        }
+    }
+
+    fn run_dedicated(mut self) {
+        let proxy = self.window.app.proxy.clone();
+        loop {
+            match private::pump_threaded(&mut self, |message| proxy.send_event(message).is_ok()) {
+                Pumped::Wait(wake_at) => self.window.wait_for_message(wake_at),
+                Pumped::Exited(message) => {
+                    // Do not notify the main thread to close the window until
+                    // after the behavior is dropped. This upholds the
+                    // requirement for RawWindowHandle by making sure that any
+                    // resources required by the behavior have had a chance to
+                    // be freed.
+                    drop(self);
+                    if let Some(message) = message {
+                        let _result = proxy.send_event(message);
+                    }
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl<Behavior, AppMessage> DriveWindow<AppMessage> for WindowDriver<Behavior, AppMessage>
+where
+    Behavior: self::WindowBehavior<AppMessage>,
+    AppMessage: Message,
+{
+    fn id(&self) -> WindowId {
+        self.window.window.id()
+    }
+
+    fn pump(&mut self) -> Pump<AppMessage> {
+        let window_id = self.id();
+        self.window.messages.0.clear_woken();
+        loop {
+            // We assert unwind safety here due to internal types on some
+            // platforms in winit use dyn trait objects that do not specify
+            // unwind safety. When a window is restarted, only the
+            // RunningWindow is reused, and its state is reset before a new
+            // behavior is initialized.
+            match std::mem::replace(&mut self.behavior, BehaviorState::Exited) {
+                BehaviorState::AwaitingRestart(decision) => match decision.try_recv() {
+                    Ok(delay) => {
+                        self.behavior = BehaviorState::Restarting(Instant::now() + delay);
+                    }
+                    Err(mpsc::TryRecvError::Empty) => {
+                        self.behavior = BehaviorState::AwaitingRestart(decision);
+                        return Pump::Wait(None);
+                    }
+                    // The event loop closed the window instead.
+                    Err(mpsc::TryRecvError::Disconnected) => return Pump::Exited(None),
+                },
+                BehaviorState::Restarting(at) => {
+                    if at > Instant::now() {
+                        self.behavior = BehaviorState::Restarting(at);
+                        return Pump::Wait(Some(at));
+                    }
+                    let restart_context = self
+                        .restart_context
+                        .as_mut()
+                        .expect("only restarted with a restart context");
+                    self.behavior = BehaviorState::Uninitialized(restart_context());
+                    self.restarts += 1;
+                    self.window.messages.0.set_restarting(false);
+                    self.window.prepare_for_restart();
+                }
+                BehaviorState::Uninitialized(context) => {
+                    match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                        self.window.initialize::<Behavior>(context)
+                    })) {
+                        Ok(Ok(behavior)) => self.behavior = BehaviorState::Running(behavior),
+                        Ok(Err(error)) => {
+                            return Pump::Exited(Some(EventLoopMessage::WindowInitError {
+                                window_id,
+                                error,
+                            }));
+                        }
+                        Err(payload) => return Pump::Panicked(payload),
+                    }
+                }
+                BehaviorState::Running(mut behavior) => {
+                    match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                        self.window.process_available(&mut behavior)
+                    })) {
+                        Ok(Ok(wake_at)) => {
+                            self.behavior = BehaviorState::Running(behavior);
+                            return Pump::Wait(wake_at);
+                        }
+                        Ok(Err(())) => {
+                            drop(behavior);
+                            return Pump::Exited(Some(EventLoopMessage::CloseWindow {
+                                window_id,
+                                reason: self.window.close_reason,
+                            }));
+                        }
+                        Err(payload) => return Pump::Panicked(payload),
+                    }
+                }
+                BehaviorState::Exited => return Pump::Wait(None),
             }
         }
+    }
+
+    fn woken(&self) -> bool {
+        self.window.messages.0.woken()
+    }
+
+    fn deliver(&mut self, message: WindowMessage<AppMessage::Window>) {
+        self.window.messages.0.deliver(message);
+    }
+
+    fn restart_after(&mut self, delay: Duration) {
+        self.window.messages.0.set_restarting(true);
+        self.behavior = BehaviorState::Restarting(Instant::now() + delay);
+    }
 
-        Ok(None)
+    fn await_restart(&mut self, decision: mpsc::Receiver<Duration>) {
+        self.window.messages.0.set_restarting(true);
+        self.behavior = BehaviorState::AwaitingRestart(decision);
+    }
+
+    fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    fn restartable(&self) -> bool {
+        self.restart_context.is_some()
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
-pub enum RedrawTarget {
-    Immediate,
-    Scheduled(Instant),
+/// The inferred state of the keyboard's lock keys.
+#[derive(Default, Debug, Clone, Copy)]
+struct LockKeys {
+    caps: Option<bool>,
+    num: Option<bool>,
 }
 
-impl From<Option<RedrawTarget>> for TimeUntilRedraw {
-    fn from(value: Option<RedrawTarget>) -> Self {
-        match value {
-            Some(RedrawTarget::Immediate) => TimeUntilRedraw::None,
-            Some(RedrawTarget::Scheduled(at)) => match at.checked_duration_since(Instant::now()) {
-                Some(remaining) if !remaining.is_zero() => TimeUntilRedraw::Some(remaining),
-                _ => TimeUntilRedraw::None,
-            },
-            None => Self::Indefinite,
+impl LockKeys {
+    fn key_pressed(&mut self, event: &KeyEvent, modifiers: Modifiers) {
+        if !event.repeat {
+            let toggled = match &event.logical_key {
+                Key::Named(NamedKey::CapsLock) => Some(&mut self.caps),
+                Key::Named(NamedKey::NumLock) => Some(&mut self.num),
+                _ => None,
+            };
+            if let Some(state) = toggled {
+                *state = state.map(|enabled| !enabled);
+                return;
+            }
+        }
+
+        let shift = modifiers.state().shift_key();
+        let mut text = event.text.as_deref().unwrap_or_default().chars();
+        let ch = match (text.next(), text.next()) {
+            (Some(ch), None) => Some(ch),
+            _ => None,
+        };
+
+        if let Some(ch) = ch.filter(|ch| ch.is_lowercase() || ch.is_uppercase()) {
+            // Caps lock inverts the effect of shift on letters.
+            self.caps = Some(ch.is_uppercase() != shift);
+        } else if !shift && event.location == KeyLocation::Numpad && is_numpad_digit(event) {
+            // Without num lock, the numpad digits act as navigation keys.
+            self.num = Some(ch.is_some_and(|ch| ch.is_ascii_digit()));
         }
     }
 }
 
-#[derive(Debug)]
-enum TimeUntilRedraw {
-    None,
-    Some(Duration),
-    Indefinite,
+fn is_numpad_digit(event: &KeyEvent) -> bool {
+    matches!(
+        event.physical_key,
+        PhysicalKey::Code(
+            KeyCode::Numpad0
+                | KeyCode::Numpad1
+                | KeyCode::Numpad2
+                | KeyCode::Numpad3
+                | KeyCode::Numpad4
+                | KeyCode::Numpad5
+                | KeyCode::Numpad6
+                | KeyCode::Numpad7
+                | KeyCode::Numpad8
+                | KeyCode::Numpad9
+        )
+    )
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum RedrawTarget {
+    Immediate,
+    Scheduled(Instant),
 }
 
 /// The behavior that drives the contents of a window.
@@ -887,7 +2253,10 @@ where
     ///
     /// This allows providing data to the window from the thread that is opening
     /// the window without requiring that `WindowBehavior` also be `Send`.
-    type Context: Send;
+    ///
+    /// Windows whose context is not `Send` must be opened using
+    /// [`WindowBuilder::open_pinned`].
+    type Context;
     /// Returns a new window builder for this behavior. When the window is
     /// initialized, a default [`Context`](Self::Context) will be passed.
     fn build<App>(app: &mut App) -> WindowBuilder<'_, Self, App, AppMessage>
@@ -931,7 +2300,7 @@ where
             + 'static,
     ) -> Result<(), EventLoopError>
     where
-        Self::Context: Default,
+        Self::Context: Default + Send,
     {
         let mut app = PendingApp::new_with_event_callback(app_callback);
         Self::open(&mut app).expect("error opening initial window");
@@ -957,7 +2326,10 @@ where
         context: Self::Context,
         app_callback: impl FnMut(AppMessage, ExecutingApp<'_, AppMessage>) -> AppMessage::Response
             + 'static,
-    ) -> Result<(), EventLoopError> {
+    ) -> Result<(), EventLoopError>
+    where
+        Self::Context: Send,
+    {
         let mut app = PendingApp::new_with_event_callback(app_callback);
         Self::open_with(&mut app, context).expect("error opening initial window");
         app.run()
@@ -971,12 +2343,13 @@ where
     ///
     /// # Errors
     ///
-    /// This function returns any error that winit may return from attempting to
-    /// open a window.
-    fn open<App>(app: &mut App) -> Result<Option<Window<AppMessage::Window>>, OsError>
+    /// This function returns an error if the window's thread could not be
+    /// spawned, or any error that winit may return from attempting to open a
+    /// window.
+    fn open<App>(app: &mut App) -> Result<Option<Window<AppMessage::Window>>, OpenError>
     where
         App: AsApplication<AppMessage> + ?Sized,
-        Self::Context: Default,
+        Self::Context: Default + Send,
     {
         Self::build(app).open()
     }
@@ -989,14 +2362,16 @@ where
     ///
     /// # Errors
     ///
-    /// This function returns any error that winit may return from attempting to
-    /// open a window.
+    /// This function returns an error if the window's thread could not be
+    /// spawned, or any error that winit may return from attempting to open a
+    /// window.
     fn open_with<App>(
         app: &mut App,
         context: Self::Context,
-    ) -> Result<Option<Window<AppMessage::Window>>, OsError>
+    ) -> Result<Option<Window<AppMessage::Window>>, OpenError>
     where
         App: AsApplication<AppMessage> + ?Sized,
+        Self::Context: Send,
     {
         Self::build_with(app, context).open()
     }
@@ -1016,6 +2391,25 @@ where
     /// Displays the contents of the window.
     fn redraw(&mut self, window: &mut RunningWindow<AppMessage>);
 
+    /// Displays the contents of the window, returning an error if the window
+    /// could not be drawn.
+    ///
+    /// By default, this function invokes [`redraw()`](Self::redraw). When
+    /// overriding this function, `redraw()` will no longer be invoked by this
+    /// crate.
+    ///
+    /// # Errors
+    ///
+    /// Errors are reported to [`PendingApp::on_error`] and then the window's
+    /// [`RuntimeErrorAction`] is applied.
+    fn try_redraw(
+        &mut self,
+        window: &mut RunningWindow<AppMessage>,
+    ) -> Result<(), AppMessage::Error> {
+        self.redraw(window);
+        Ok(())
+    }
+
     /// Invoked once a window is fully initialized.
     ///
     /// This is invoked after the window has been presented to the user, if it
@@ -1024,7 +2418,8 @@ where
     fn initialized(&mut self, window: &mut RunningWindow<AppMessage>) {}
 
     /// The window has been requested to be closed. This can happen as a result
-    /// of the user clicking the close button.
+    /// of the user clicking the close button, or the app being asked to quit
+    /// using [`App::quit`].
     ///
     /// If the window should be closed, return true. To prevent closing the
     /// window, return false.
@@ -1033,6 +2428,11 @@ where
         true
     }
 
+    /// A request to quit the app has been cancelled because another window
+    /// returned false from [`close_requested()`](Self::close_requested).
+    #[allow(unused_variables)]
+    fn quit_cancelled(&mut self, window: &mut RunningWindow<AppMessage>) {}
+
     /// The window has gained or lost keyboard focus.
     /// [`RunningWindow::focused()`] returns the current state.
     #[allow(unused_variables)]
@@ -1045,6 +2445,15 @@ where
 
     /// The window's scale factor has changed. [`RunningWindow::scale()`]
     /// returns the current scale.
+    ///
+    /// The size the window is resized to as a result of the change can be
+    /// customized using [`RunningWindow::request_scaled_inner_size`]. This is
+    /// best-effort: unless the window runs on the event loop, the event loop
+    /// waits for this function to return for up to the window's
+    /// [`resize_sync_timeout`](WindowAttributes::resize_sync_timeout), or one
+    /// frame if it is not set. If the window handles the change later, the
+    /// operating system's suggested size is used and requesting a size returns
+    /// false.
     #[allow(unused_variables)]
     fn scale_factor_changed(&mut self, window: &mut RunningWindow<AppMessage>) {}
 
@@ -1053,6 +2462,25 @@ where
     #[allow(unused_variables)]
     fn resized(&mut self, window: &mut RunningWindow<AppMessage>) {}
 
+    /// The window has been resized. [`RunningWindow::inner_size()`]
+    /// returns the current size.
+    ///
+    /// By default, this function invokes [`resized()`](Self::resized). When
+    /// overriding this function, `resized()` will no longer be invoked by
+    /// this crate.
+    ///
+    /// # Errors
+    ///
+    /// Errors are reported to [`PendingApp::on_error`] and then the window's
+    /// [`RuntimeErrorAction`] is applied.
+    fn try_resized(
+        &mut self,
+        window: &mut RunningWindow<AppMessage>,
+    ) -> Result<(), AppMessage::Error> {
+        self.resized(window);
+        Ok(())
+    }
+
     /// The window has been moved. [`RunningWindow::outer_position()`] returns
     /// the current position.
     #[allow(unused_variables)]
@@ -1080,6 +2508,9 @@ where
     fn received_character(&mut self, window: &mut RunningWindow<AppMessage>, char: char) {}
 
     /// A keyboard event occurred while the window was focused.
+    ///
+    /// When the window loses focus, this function is invoked with a synthetic
+    /// release event for each key that is still pressed.
     #[allow(unused_variables)]
     fn keyboard_input(
         &mut self,
@@ -1128,7 +2559,27 @@ where
     ) {
     }
 
+    /// The window has scrolled by `delta` physical pixels.
+    ///
+    /// This function is only invoked after scroll processing has been enabled
+    /// with [`RunningWindow::set_scroll_processing`]. It is invoked after
+    /// [`WindowBehavior::mouse_wheel`] for each scroll event, and once per
+    /// frame while momentum scrolling is active.
+    #[allow(unused_variables)]
+    fn scrolled(
+        &mut self,
+        window: &mut RunningWindow<AppMessage>,
+        device_id: DeviceId,
+        delta: PhysicalPosition<f64>,
+        phase: ScrollPhase,
+    ) {
+    }
+
     /// A mouse button was pressed or released.
+    ///
+    /// When the window loses focus, this function is invoked to release each
+    /// button that is still pressed. During these invocations,
+    /// [`RunningWindow::is_synthetic_event`] returns true.
     #[allow(unused_variables)]
     fn mouse_input(
         &mut self,
@@ -1223,7 +2674,7 @@ pub trait Run: WindowBehavior<()> {
     /// information.
     fn run() -> Result<(), EventLoopError>
     where
-        Self::Context: Default,
+        Self::Context: Default + Send,
     {
         let mut app = PendingApp::new();
         Self::open(&mut app).expect("error opening initial window");
@@ -1241,7 +2692,10 @@ pub trait Run: WindowBehavior<()> {
     /// loop exiting due to an error. See
     /// [`EventLoop::run`](winit::event_loop::EventLoop::run) for more
     /// information.
-    fn run_with(context: Self::Context) -> Result<(), EventLoopError> {
+    fn run_with(context: Self::Context) -> Result<(), EventLoopError>
+    where
+        Self::Context: Send,
+    {
         let mut app = PendingApp::new();
         Self::open_with(&mut app, context).expect("error opening initial window");
         app.run()
@@ -1249,3 +2703,87 @@ pub trait Run: WindowBehavior<()> {
 }
 
 impl<T> Run for T where T: WindowBehavior<()> {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::{response_sender, TrySendError, Window};
+    use crate::private::{OpenedWindow, WindowReceiver, WindowSender};
+
+    fn window() -> (Window<u32>, Arc<WindowSender<u32>>, WindowReceiver<u32>) {
+        let (sender, receiver) = WindowSender::queue();
+        let sender = Arc::new(sender);
+        let window = Window::new(OpenedWindow::pending(), &sender);
+        (window, sender, receiver)
+    }
+
+    /// Sends messages until the window's queue is full.
+    fn fill(window: &Window<u32>) {
+        let mut message = 0;
+        while window.try_send(message).is_ok() {
+            message += 1;
+        }
+    }
+
+    #[test]
+    fn full_queues_return_the_message() {
+        let (window, _sender, _receiver) = window();
+        fill(&window);
+        assert_eq!(window.try_send(1), Err(TrySendError::Full(1)));
+        assert_eq!(
+            window.send_timeout(2, Duration::from_millis(10)),
+            Err(TrySendError::Full(2))
+        );
+    }
+
+    #[test]
+    fn closed_windows_return_the_message() {
+        let (window, sender, receiver) = window();
+        drop(receiver);
+        assert_eq!(window.try_send(1), Err(TrySendError::Closed(1)));
+        assert_eq!(
+            window.send_timeout(2, Duration::from_secs(1)),
+            Err(TrySendError::Closed(2))
+        );
+
+        drop(sender);
+        assert_eq!(window.try_send(3), Err(TrySendError::Closed(3)));
+        assert_eq!(
+            window.send_timeout(4, Duration::from_secs(1)),
+            Err(TrySendError::Closed(4))
+        );
+    }
+
+    #[test]
+    fn closed_takes_precedence_over_full() {
+        let (window, _sender, receiver) = window();
+        fill(&window);
+        drop(receiver);
+        assert_eq!(window.try_send(1), Err(TrySendError::Closed(1)));
+    }
+
+    #[test]
+    fn local_windows_can_send_repeatedly() {
+        let responses = mpsc::sync_channel::<u32>(1);
+        let (answered, all_answered) = mpsc::channel();
+        let senders = [
+            response_sender(&responses, true),
+            response_sender(&responses, true),
+        ];
+        // Answer each message the way the event loop does, which blocks until
+        // the response is accepted.
+        thread::spawn(move || {
+            for (response, sender) in senders.into_iter().enumerate() {
+                let _result = sender.send(u32::try_from(response).unwrap());
+            }
+            answered.send(()).unwrap();
+        });
+        all_answered
+            .recv_timeout(Duration::from_secs(5))
+            .expect("event loop blocked answering a local window");
+        assert!(responses.1.try_recv().is_err());
+    }
+}